@@ -0,0 +1,75 @@
+//! generates maps without opening the game window and prints statistics about them,
+//! to compare generator changes over many seeds
+//!
+//! `gerador [--seed N] [--count N] [--depth N] [--dump]` plus the game's
+//! `--generator`, `--layout`, `--corridors`, `--corridor-width`, `--braid`, `--width`
+//! and `--height` options
+
+extern crate rogue;
+
+use std::env;
+use std::process;
+
+use rogue::{Dungeon, Object};
+use rogue::{arg_value, export, make_level, parse_generation, parse_seed};
+
+fn main() {
+    // `--count N` maps are generated from consecutive seeds, starting at `--seed`
+    let first_seed = parse_seed();
+    let count: u32 = arg_value("--count").map_or(10, |aux| {
+        aux.parse().expect("--count precisa ser um numero inteiro positivo")
+    });
+    let depth: i32 = arg_value("--depth").map_or(1, |aux| {
+        aux.parse().expect("--depth precisa ser um numero inteiro positivo")
+    });
+    // `--dump` also writes every map as text, JSON and an image
+    let dump = env::args().any(|arg| arg == "--dump");
+
+    let mut dungeon = Dungeon::new(first_seed, parse_generation());
+
+    println!("seed\tsalas\tchao%\tagua%\tregioes\tescadas\tmonstros\titens\ttentativas");
+    let (mut floor_total, mut water_total, mut rooms_total) = (0.0, 0.0, 0);
+    // the seeds whose level couldn't be generated are reported and left out of the averages
+    let mut failed = 0;
+    // a map that couldn't be exported still counts in the averages, but fails the run
+    let mut export_failed = false;
+    for i in 0..count {
+        let seed = first_seed.wrapping_add(i);
+        dungeon.set_seed(seed);
+        let mut objects = vec![Object::player(0, 0)];
+        let (map, stats) = match make_level(depth, &dungeon, &mut objects) {
+            Ok(level) => level,
+            Err(error) => {
+                eprintln!("Seed {}: {}", seed, error);
+                failed += 1;
+                continue;
+            }
+        };
+
+        let tiles = (map.width() * map.height()) as f32;
+        let floor = 100.0 * stats.floor_tiles as f32 / tiles;
+        let water = 100.0 * stats.water_tiles as f32 / tiles;
+        let stairs = stats.stairs_distance.map_or("-".to_string(), |distance| distance.to_string());
+        println!("{}\t{}\t{:.1}\t{:.1}\t{}\t{}\t{}\t{}\t{}",
+                 seed, stats.rooms, floor, water, stats.regions_found, stairs, stats.monsters, stats.items, stats.attempts);
+        floor_total += floor;
+        water_total += water;
+        rooms_total += stats.rooms;
+
+        if dump {
+            let prefix = format!("mapa_{}_nivel_{}", seed, depth);
+            if let Err(error) = export::export_map(&map, &objects, &prefix) {
+                eprintln!("Nao foi possivel exportar {}: {}", prefix, error);
+                export_failed = true;
+            }
+        }
+    }
+
+    let generated = count - failed;
+    if generated > 0 {
+        println!("media\t{:.1}\t{:.1}\t{:.1}", rooms_total as f32 / generated as f32, floor_total / generated as f32, water_total / generated as f32);
+    }
+    if failed > 0 || export_failed {
+        process::exit(1);
+    }
+}
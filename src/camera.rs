@@ -0,0 +1,175 @@
+use std::cmp;
+
+use enums::CameraMode;
+use map::Map;
+
+/// how far the player can wander from the center before the dead-zone camera follows
+const DEAD_ZONE_WIDTH: i32 = 8;
+const DEAD_ZONE_HEIGHT: i32 = 5;
+/// how close to the edge of the view the player gets before the edge-scroll camera turns the page
+const EDGE_MARGIN: i32 = 5;
+
+/// the part of the map that is on screen, kept inside the map's bounds.
+/// Rendering and the mouse both go through it to turn map positions into screen ones and back
+#[derive(Clone, Copy, Debug)]
+pub struct Camera {
+    mode: CameraMode,
+    /// map position of the top left corner of the view
+    x: i32,
+    y: i32,
+    width: i32,
+    height: i32,
+}
+
+impl Camera {
+    pub fn new(mode: CameraMode, width: i32, height: i32) -> Self {
+        Camera { mode: mode, x: 0, y: 0, width: width, height: height }
+    }
+
+    pub fn width(&self) -> i32 {
+        self.width
+    }
+
+    pub fn height(&self) -> i32 {
+        self.height
+    }
+
+    /// puts the target in the middle of the view, for a new level or a teleport
+    pub fn center_on(&mut self, (x, y): (i32, i32), map: &Map) {
+        self.x = x - self.width / 2;
+        self.y = y - self.height / 2;
+        self.clamp(map);
+    }
+
+    /// moves the view after the target moved, the way the camera mode says
+    pub fn follow(&mut self, (x, y): (i32, i32), map: &Map) {
+        match self.mode {
+            CameraMode::Centered => {
+                self.x = x - self.width / 2;
+                self.y = y - self.height / 2;
+            }
+            CameraMode::DeadZone => {
+                self.x = follow_axis(self.x, x, self.width, DEAD_ZONE_WIDTH);
+                self.y = follow_axis(self.y, y, self.height, DEAD_ZONE_HEIGHT);
+            }
+            CameraMode::EdgeScroll => {
+                self.x = scroll_axis(self.x, x, self.width);
+                self.y = scroll_axis(self.y, y, self.height);
+            }
+        }
+        self.clamp(map);
+    }
+
+    /// the map position shown at the top left corner of the screen
+    pub fn offset(&self) -> (i32, i32) {
+        (self.x, self.y)
+    }
+
+    /// the screen cell showing a map position, `None` if the position is out of view
+    pub fn to_screen(self, x: i32, y: i32) -> Option<(i32, i32)> {
+        let (x, y) = (x - self.x, y - self.y);
+        if x >= 0 && y >= 0 && x < self.width && y < self.height {
+            Some((x, y))
+        } else {
+            None
+        }
+    }
+
+    /// the map position under a screen cell, `None` if the cell is outside the view
+    pub fn to_map(self, x: i32, y: i32) -> Option<(i32, i32)> {
+        if x >= 0 && y >= 0 && x < self.width && y < self.height {
+            Some((x + self.x, y + self.y))
+        } else {
+            None
+        }
+    }
+
+    /// keeps the view inside the map, a map smaller than the view stays in the top left corner
+    fn clamp(&mut self, map: &Map) {
+        self.x = cmp::max(0, cmp::min(self.x, map.width() - self.width));
+        self.y = cmp::max(0, cmp::min(self.y, map.height() - self.height));
+    }
+}
+
+/// moves the view along one axis only as much as needed to keep the target inside the dead zone
+fn follow_axis(start: i32, target: i32, size: i32, dead_zone: i32) -> i32 {
+    let center = start + size / 2;
+    if target < center - dead_zone {
+        target + dead_zone - size / 2
+    } else if target > center + dead_zone {
+        target - dead_zone - size / 2
+    } else {
+        start
+    }
+}
+
+/// turns half a view along one axis when the target gets near its edge
+fn scroll_axis(start: i32, target: i32, size: i32) -> i32 {
+    if target < start + EDGE_MARGIN {
+        start - size / 2
+    } else if target >= start + size - EDGE_MARGIN {
+        start + size / 2
+    } else {
+        start
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use Tile;
+
+    fn map() -> Map {
+        Map::new(100, 60, Tile::empty())
+    }
+
+    #[test]
+    fn centered_follows_every_step() {
+        let mut camera = Camera::new(CameraMode::Centered, 20, 10);
+        camera.follow((50, 30), &map());
+        assert_eq!(camera.offset(), (40, 25));
+        camera.follow((51, 30), &map());
+        assert_eq!(camera.offset(), (41, 25));
+    }
+
+    #[test]
+    fn dead_zone_waits_until_the_target_leaves_it() {
+        let mut camera = Camera::new(CameraMode::DeadZone, 40, 20);
+        camera.center_on((50, 30), &map());
+        assert_eq!(camera.offset(), (30, 20));
+        camera.follow((50 + DEAD_ZONE_WIDTH, 30), &map());
+        assert_eq!(camera.offset(), (30, 20));
+        camera.follow((51 + DEAD_ZONE_WIDTH, 30), &map());
+        assert_eq!(camera.offset(), (31, 20));
+    }
+
+    #[test]
+    fn edge_scroll_turns_half_a_view() {
+        let mut camera = Camera::new(CameraMode::EdgeScroll, 40, 20);
+        camera.center_on((50, 30), &map());
+        camera.follow((30 + 40 - EDGE_MARGIN, 30), &map());
+        assert_eq!(camera.offset(), (50, 20));
+    }
+
+    #[test]
+    fn clamp_keeps_the_view_inside_the_map() {
+        let mut camera = Camera::new(CameraMode::Centered, 20, 10);
+        camera.follow((0, 0), &map());
+        assert_eq!(camera.offset(), (0, 0));
+        camera.follow((99, 59), &map());
+        assert_eq!(camera.offset(), (80, 50));
+        // a map smaller than the view stays in the top left corner
+        camera.follow((5, 5), &Map::new(10, 8, Tile::empty()));
+        assert_eq!(camera.offset(), (0, 0));
+    }
+
+    #[test]
+    fn to_map_and_to_screen_are_each_others_inverse() {
+        let mut camera = Camera::new(CameraMode::Centered, 20, 10);
+        camera.follow((50, 30), &map());
+        assert_eq!(camera.to_map(3, 4), Some((43, 29)));
+        assert_eq!(camera.to_screen(43, 29), Some((3, 4)));
+        assert_eq!(camera.to_screen(39, 29), None);
+        assert_eq!(camera.to_map(20, 0), None);
+    }
+}
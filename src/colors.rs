@@ -0,0 +1,42 @@
+//! the colors of the game: libtcod's own with the `tcod` feature, otherwise a plain copy
+//! of the ones used here, so the map generator builds without libtcod
+
+#[cfg(feature = "tcod")]
+pub use tcod::colors::*;
+
+#[cfg(not(feature = "tcod"))]
+pub use self::plain::*;
+
+#[cfg(not(feature = "tcod"))]
+mod plain {
+    #[derive(Copy, Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+    pub struct Color {
+        pub r: u8,
+        pub g: u8,
+        pub b: u8,
+    }
+
+    /// the color `coefficient` of the way from `from` to `to`, like libtcod's
+    pub fn lerp(from: Color, to: Color, coefficient: f32) -> Color {
+        let channel = |from: u8, to: u8| (from as f32 + (to as f32 - from as f32) * coefficient) as u8;
+        Color { r: channel(from.r, to.r), g: channel(from.g, to.g), b: channel(from.b, to.b) }
+    }
+
+    pub const BLACK: Color = Color { r: 0, g: 0, b: 0 };
+    pub const GREY: Color = Color { r: 127, g: 127, b: 127 };
+    pub const WHITE: Color = Color { r: 255, g: 255, b: 255 };
+    pub const DARK_SEPIA: Color = Color { r: 94, g: 75, b: 47 };
+    pub const LIGHT_RED: Color = Color { r: 255, g: 63, b: 63 };
+    pub const LIGHT_YELLOW: Color = Color { r: 255, g: 255, b: 63 };
+    pub const LIGHT_GREEN: Color = Color { r: 63, g: 255, b: 63 };
+    pub const LIGHT_MAGENTA: Color = Color { r: 255, g: 63, b: 207 };
+    pub const RED: Color = Color { r: 255, g: 0, b: 0 };
+    pub const ORANGE: Color = Color { r: 255, g: 127, b: 0 };
+    pub const YELLOW: Color = Color { r: 255, g: 255, b: 0 };
+    pub const GREEN: Color = Color { r: 0, g: 255, b: 0 };
+    pub const BLUE: Color = Color { r: 0, g: 0, b: 255 };
+    pub const MAGENTA: Color = Color { r: 255, g: 0, b: 191 };
+    pub const DARK_RED: Color = Color { r: 191, g: 0, b: 0 };
+    pub const DARK_BLUE: Color = Color { r: 0, g: 0, b: 191 };
+    pub const DARKEST_BLUE: Color = Color { r: 0, g: 0, b: 63 };
+}
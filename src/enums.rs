@@ -45,5 +45,93 @@ pub enum Ai {
     Scared {
         previous: Box<Ai>,
         turns: i32
-    }
+    },
+    // heard an alarm, hunts the player even out of sight
+    Alerted {
+        previous: Box<Ai>,
+        turns: i32
+    },
+    // fell through a pit, leaves the level at the end of the turn
+    Fallen
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Generator {
+    Rooms,
+    Perlin,
+    Caves,
+    Maze,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum MessageCategory {
+    Combat,
+    Item,
+    System,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum RoomLayout {
+    Overlapping,
+    Bsp,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum CorridorStyle {
+    Straight,
+    Winding,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Backend {
+    Tcod,
+    Terminal,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum CameraMode {
+    Centered,
+    DeadZone,
+    EdgeScroll,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum DoorState {
+    Open,
+    Closed,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum TrapKind {
+    Dart,
+    Teleport,
+    Alarm,
+    ConfusionGas,
+    Pit,
+}
+
+// what a secret tile turns into once it is found
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Secret {
+    Door,
+    Passage,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Terrain {
+    Floor,
+    Wall,
+    ShallowWater,
+    DeepWater,
+    Bridge,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum RoomShape {
+    Rectangle,
+    Circle,
+    Cross,
+    // the quarter of the room that is left as rock, clockwise from the top left
+    LShape(i32),
+    PillaredHall,
 }
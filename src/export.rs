@@ -0,0 +1,200 @@
+use std::fs::File;
+use std::io::{self, Write};
+
+use serde_json;
+use colors::{self, Color};
+
+use map::Map;
+use {Object, PLAYER};
+
+/// size in pixels of a tile in the exported image
+const PNG_SCALE: i32 = 2;
+/// zlib stored blocks can't hold more than this many bytes
+const MAX_STORED_BLOCK: usize = 65535;
+
+#[derive(Serialize)]
+struct ExportedObject {
+    name: String,
+    char: String,
+    kind: &'static str,
+    x: i32,
+    y: i32,
+}
+
+#[derive(Serialize)]
+struct ExportedMap {
+    width: i32,
+    height: i32,
+    /// one string per row, with the tile glyphs only
+    tiles: Vec<String>,
+    objects: Vec<ExportedObject>,
+}
+
+/// writes `<prefix>.txt`, `<prefix>.json` and `<prefix>.png` for the map and its objects
+pub fn export_map(map: &Map, objects: &[Object], prefix: &str) -> io::Result<()> {
+    export_ascii(map, objects, &format!("{}.txt", prefix))?;
+    export_json(map, objects, &format!("{}.json", prefix))?;
+    export_png(map, objects, &format!("{}.png", prefix))
+}
+
+/// the map as a grid of glyphs, with the objects drawn over the tiles
+pub fn export_ascii(map: &Map, objects: &[Object], path: &str) -> io::Result<()> {
+    let mut grid: Vec<Vec<char>> = (0..map.height())
+        .map(|y| (0..map.width()).map(|x| map[(x, y)].char).collect())
+        .collect();
+    for (_, object) in drawing_order(objects) {
+        if map.in_bounds(object.x, object.y) {
+            grid[object.y as usize][object.x as usize] = object.char;
+        }
+    }
+
+    let mut file = File::create(path)?;
+    for row in grid {
+        writeln!(file, "{}", row.into_iter().collect::<String>())?;
+    }
+    Ok(())
+}
+
+pub fn export_json(map: &Map, objects: &[Object], path: &str) -> io::Result<()> {
+    let exported = ExportedMap {
+        width: map.width(),
+        height: map.height(),
+        tiles: (0..map.height())
+            .map(|y| (0..map.width()).map(|x| map[(x, y)].char).collect())
+            .collect(),
+        objects: objects.iter().enumerate()
+            .map(|(id, object)| ExportedObject {
+                name: object.name.clone(),
+                char: object.char.to_string(),
+                kind: kind(id, object),
+                x: object.x,
+                y: object.y,
+            })
+            .collect(),
+    };
+    let json = serde_json::to_string_pretty(&exported)
+        .map_err(|error| io::Error::other(error.to_string()))?;
+    let mut file = File::create(path)?;
+    file.write_all(json.as_bytes())
+}
+
+/// an RGB image with every tile in its light color and the objects as solid markers
+pub fn export_png(map: &Map, objects: &[Object], path: &str) -> io::Result<()> {
+    let mut colors: Vec<Color> = (0..map.height())
+        .flat_map(|y| (0..map.width()).map(move |x| (x, y)))
+        .map(|(x, y)| map[(x, y)].light_color)
+        .collect();
+    for (id, object) in drawing_order(objects) {
+        if map.in_bounds(object.x, object.y) {
+            colors[(object.y * map.width() + object.x) as usize] = marker_color(id, object);
+        }
+    }
+
+    let (width, height) = (map.width() * PNG_SCALE, map.height() * PNG_SCALE);
+    // every scanline starts with its filter type, 0 for none
+    let mut raw = Vec::with_capacity((height * (width * 3 + 1)) as usize);
+    for y in 0..height {
+        raw.push(0);
+        for x in 0..width {
+            let color = colors[((y / PNG_SCALE) * map.width() + x / PNG_SCALE) as usize];
+            raw.extend_from_slice(&[color.r, color.g, color.b]);
+        }
+    }
+
+    let mut header = vec![];
+    header.extend_from_slice(&be_bytes(width as u32));
+    header.extend_from_slice(&be_bytes(height as u32));
+    // 8 bits per channel, RGB, default compression, filtering and no interlacing
+    header.extend_from_slice(&[8, 2, 0, 0, 0]);
+
+    let mut file = File::create(path)?;
+    file.write_all(&[0x89, b'P', b'N', b'G', b'\r', b'\n', 0x1a, b'\n'])?;
+    write_chunk(&mut file, b"IHDR", &header)?;
+    write_chunk(&mut file, b"IDAT", &zlib_stored(&raw))?;
+    write_chunk(&mut file, b"IEND", &[])
+}
+
+/// the objects in the order they are drawn, the player last so it is never hidden
+fn drawing_order(objects: &[Object]) -> Vec<(usize, &Object)> {
+    let mut ordered: Vec<(usize, &Object)> = objects.iter().enumerate().skip(PLAYER + 1).collect();
+    ordered.extend(objects.get(PLAYER).map(|player| (PLAYER, player)));
+    ordered
+}
+
+fn kind(id: usize, object: &Object) -> &'static str {
+    if id == PLAYER {
+        "player"
+    } else if object.name.starts_with("stairs") {
+        "stairs"
+    } else if object.item.is_some() {
+        "item"
+    } else if object.trap.is_some() {
+        "trap"
+    } else if object.fighter.is_some() && object.alive {
+        "monster"
+    } else {
+        "other"
+    }
+}
+
+fn marker_color(id: usize, object: &Object) -> Color {
+    match kind(id, object) {
+        "player" => colors::WHITE,
+        "stairs" => colors::MAGENTA,
+        "item" => colors::YELLOW,
+        "trap" => colors::ORANGE,
+        "monster" => colors::RED,
+        _ => object.color,
+    }
+}
+
+fn write_chunk<W: Write>(out: &mut W, kind: &[u8; 4], data: &[u8]) -> io::Result<()> {
+    out.write_all(&be_bytes(data.len() as u32))?;
+    out.write_all(kind)?;
+    out.write_all(data)?;
+    // the checksum covers the chunk type and its data, not the length
+    let mut crc = crc32_update(0xffff_ffff, kind);
+    crc = crc32_update(crc, data);
+    out.write_all(&be_bytes(crc ^ 0xffff_ffff))
+}
+
+/// wraps the data in a zlib stream without compressing it, which every decoder accepts
+fn zlib_stored(data: &[u8]) -> Vec<u8> {
+    let mut out = vec![0x78, 0x01];
+    let mut blocks = data.chunks(MAX_STORED_BLOCK).peekable();
+    if blocks.peek().is_none() {
+        out.extend_from_slice(&[1, 0, 0, 0xff, 0xff]);
+    }
+    while let Some(block) = blocks.next() {
+        let last = blocks.peek().is_none();
+        let len = block.len() as u16;
+        out.push(if last { 1 } else { 0 });
+        out.extend_from_slice(&[len as u8, (len >> 8) as u8, !len as u8, (!len >> 8) as u8]);
+        out.extend_from_slice(block);
+    }
+    out.extend_from_slice(&be_bytes(adler32(data)));
+    out
+}
+
+fn crc32_update(mut crc: u32, data: &[u8]) -> u32 {
+    for &byte in data {
+        crc ^= byte as u32;
+        for _ in 0..8 {
+            crc = if crc & 1 == 1 { 0xedb8_8320 ^ (crc >> 1) } else { crc >> 1 };
+        }
+    }
+    crc
+}
+
+fn adler32(data: &[u8]) -> u32 {
+    let (mut a, mut b) = (1u32, 0u32);
+    for &byte in data {
+        a = (a + byte as u32) % 65521;
+        b = (b + a) % 65521;
+    }
+    (b << 16) | a
+}
+
+fn be_bytes(value: u32) -> [u8; 4] {
+    [(value >> 24) as u8, (value >> 16) as u8, (value >> 8) as u8, value as u8]
+}
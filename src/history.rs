@@ -0,0 +1,187 @@
+#[cfg(feature = "tcod")]
+use std::cmp;
+use std::collections::VecDeque;
+
+use colors::Color;
+#[cfg(any(feature = "tcod", test))]
+use colors;
+
+use enums::MessageCategory;
+#[cfg(any(feature = "tcod", test))]
+use renderer;
+#[cfg(feature = "tcod")]
+use renderer::{KeyCode, Renderer, TextAlignment};
+
+/// how many messages are kept, the oldest ones are dropped after that
+const MAX_MESSAGES: usize = 1000;
+/// how many lines a page up or down scrolls the log viewer
+#[cfg(feature = "tcod")]
+const PAGE_STEP: i32 = 20;
+
+#[derive(Clone, Debug)]
+pub struct Message {
+    pub text: String,
+    pub color: Color,
+    pub turn: u32,
+    pub category: MessageCategory,
+}
+
+/// every message of the game, stamped with the turn it happened on.
+/// The panel only shows the latest ones, the log viewer all of them
+#[derive(Clone, Debug)]
+pub struct Messages {
+    entries: VecDeque<Message>,
+    turn: u32,
+}
+
+impl Messages {
+    pub fn new() -> Self {
+        Messages { entries: VecDeque::new(), turn: 1 }
+    }
+
+    pub fn push(&mut self, text: String, color: Color, category: MessageCategory) {
+        if self.entries.len() == MAX_MESSAGES {
+            self.entries.pop_front();
+        }
+        let turn = self.turn;
+        self.entries.push_back(Message { text: text, color: color, turn: turn, category: category });
+    }
+
+    /// the messages from the oldest to the latest
+    pub fn iter(&self) -> impl DoubleEndedIterator<Item = &Message> {
+        self.entries.iter()
+    }
+
+    pub fn next_turn(&mut self) {
+        self.turn += 1;
+    }
+}
+
+/// which categories the log viewer shows, in the order Tab goes through them
+#[cfg(feature = "tcod")]
+const FILTERS: [Option<MessageCategory>; 4] = [
+    None,
+    Some(MessageCategory::Combat),
+    Some(MessageCategory::Item),
+    Some(MessageCategory::System),
+];
+
+/// takes over the screen with the whole message log until Escape is pressed.
+/// The arrows and page keys scroll, Tab changes the category shown and `/` searches the text
+#[cfg(feature = "tcod")]
+pub fn show_log(renderer: &mut dyn Renderer, messages: &Messages) {
+    let (width, height) = (renderer.width(), renderer.height() - 2);
+    let mut filter = 0;
+    let mut search = String::new();
+    let mut typing = false;
+    // how many lines the view is scrolled up from the latest message
+    let mut scroll = 0;
+
+    loop {
+        let lines = log_lines(messages, FILTERS[filter], &search, width - 2);
+        let bottom = cmp::max(lines.len() as i32 - height, 0);
+        scroll = cmp::max(0, cmp::min(scroll, bottom));
+
+        renderer.fill(0, 0, renderer.width(), renderer.height(), colors::BLACK, 1.0);
+        let first = (bottom - scroll) as usize;
+        for (y, &(ref line, color)) in lines.iter().skip(first).take(height as usize).enumerate() {
+            renderer.print(1, y as i32, line, color, TextAlignment::Left);
+        }
+
+        let category = FILTERS[filter].map_or("todas", |category| category.name());
+        let status = if typing {
+            format!("Buscar: {}_", search)
+        } else {
+            format!("Mensagens: {}  Busca: {}", category, if search.is_empty() { "-" } else { &search })
+        };
+        renderer.print(1, height, &status, colors::WHITE, TextAlignment::Left);
+        renderer.print(1, height + 1, "setas/PgUp/PgDn rolam, Tab filtra, / busca, Esc fecha",
+                       colors::LIGHT_GREY, TextAlignment::Left);
+        renderer.flush();
+
+        let key = renderer.wait_for_keypress();
+        if typing {
+            match key.code {
+                KeyCode::Enter | KeyCode::Escape => typing = false,
+                KeyCode::Backspace => {
+                    search.pop();
+                }
+                _ if key.printable != '\0' => search.push(key.printable),
+                _ => (),
+            }
+            scroll = 0;
+            continue;
+        }
+        match (key.code, key.printable) {
+            (KeyCode::Up, _) => scroll += 1,
+            (KeyCode::Down, _) => scroll -= 1,
+            (KeyCode::PageUp, _) => scroll += PAGE_STEP,
+            (KeyCode::PageDown, _) => scroll -= PAGE_STEP,
+            (KeyCode::Tab, _) => {
+                filter = (filter + 1) % FILTERS.len();
+                scroll = 0;
+            }
+            (_, '/') => {
+                typing = true;
+                search.clear();
+            }
+            (KeyCode::Escape, _) => break,
+            _ => (),
+        }
+    }
+}
+
+/// the messages that pass the filter and the search, wrapped and stamped with their turn
+#[cfg(any(feature = "tcod", test))]
+fn log_lines(messages: &Messages, filter: Option<MessageCategory>, search: &str, width: i32) -> Vec<(String, Color)> {
+    let search = search.to_lowercase();
+    let mut lines = vec![];
+    for entry in messages.iter() {
+        if filter.is_some_and(|category| category != entry.category) ||
+            !entry.text.to_lowercase().contains(&search) {
+            continue;
+        }
+        let stamp = format!("[{:>5}] ", entry.turn);
+        let indent = stamp.len() as i32;
+        for (i, line) in renderer::wrap(&entry.text, width - indent).into_iter().enumerate() {
+            let prefix = if i == 0 { stamp.clone() } else { " ".repeat(indent as usize) };
+            lines.push((prefix + &line, entry.color));
+        }
+    }
+    lines
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn messages() -> Messages {
+        let mut messages = Messages::new();
+        messages.push("O orc ataca".into(), colors::RED, MessageCategory::Combat);
+        messages.next_turn();
+        messages.push("Voce pega uma pocao".into(), colors::GREEN, MessageCategory::Item);
+        messages
+    }
+
+    #[test]
+    fn log_lines_are_stamped_with_their_turn() {
+        let lines = log_lines(&messages(), None, "", 40);
+        assert_eq!(lines, vec![("[    1] O orc ataca".to_string(), colors::RED),
+                               ("[    2] Voce pega uma pocao".to_string(), colors::GREEN)]);
+    }
+
+    #[test]
+    fn log_lines_filter_by_category_and_search() {
+        let lines = log_lines(&messages(), Some(MessageCategory::Item), "", 40);
+        assert_eq!(lines.len(), 1);
+        let lines = log_lines(&messages(), None, "ORC", 40);
+        assert_eq!(lines, vec![("[    1] O orc ataca".to_string(), colors::RED)]);
+    }
+
+    #[test]
+    fn log_lines_indent_the_wrapped_lines() {
+        let lines = log_lines(&messages(), Some(MessageCategory::Item), "", 18);
+        assert_eq!(lines.iter().map(|line| line.0.as_str()).collect::<Vec<_>>(),
+                   vec!["[    2] Voce pega", "        uma pocao"]);
+    }
+}
@@ -0,0 +1,3559 @@
+#[cfg(feature = "tcod")]
+extern crate tcod;
+extern crate rand;
+extern crate bresenham;
+extern crate serde;
+#[macro_use]
+extern crate serde_derive;
+extern crate serde_json;
+
+use std::cmp;
+use std::env;
+use std::collections::{BinaryHeap, HashMap, VecDeque};
+use std::cmp::Reverse;
+#[cfg(feature = "tcod")]
+use std::mem;
+use std::sync::atomic::AtomicBool;
+#[cfg(feature = "tcod")]
+use std::sync::atomic::Ordering;
+
+#[cfg(feature = "tcod")]
+use tcod::map::{Map as FovMap, FovAlgorithm};
+use rand::{Rng, SeedableRng, StdRng};
+use bresenham::Bresenham;
+
+#[cfg(feature = "tcod")]
+use camera::Camera;
+use colors::Color;
+use history::Messages;
+use map::Map;
+#[cfg(feature = "tcod")]
+use renderer::{Event, Key, Mouse, Renderer, TextAlignment};
+
+/// set by `--debug`, which sends the generation details to stderr
+static DEBUG: AtomicBool = AtomicBool::new(false);
+
+/// prints to stderr when `--debug` is on. Never to stdout, where the terminal
+/// backend draws the game
+macro_rules! debug {
+    ($($arg:tt)*) => (if ::DEBUG.load(::std::sync::atomic::Ordering::Relaxed) { eprintln!($($arg)*); })
+}
+
+// only the game uses the camera and the renderers, they are built without it for their tests
+#[cfg_attr(not(feature = "tcod"), allow(dead_code))]
+mod camera;
+mod colors;
+pub mod enums;
+pub mod export;
+mod history;
+pub mod map;
+#[cfg(feature = "tcod")]
+mod minimap;
+mod noise;
+mod prefab;
+#[cfg_attr(not(feature = "tcod"), allow(dead_code))]
+mod renderer;
+mod spawn;
+mod theme;
+
+
+pub const PLAYER: usize = 0;
+
+// actual size of the window
+const SCREEN_WIDTH: i32 = 80;
+const SCREEN_HEIGHT: i32 = 50;
+
+// default size of the map, `--width` and `--height` change it
+pub const MAP_WIDTH: i32 = 800 + SCREEN_WIDTH + 1;
+pub const MAP_HEIGHT: i32 = 450 + SCREEN_HEIGHT + 1; 
+const MAP_MIN_SIZE: i32 = ROOM_MAX_SIZE + 2;
+
+// Panel
+#[cfg(feature = "tcod")]
+const BAR_WIDTH: i32 = 20;
+#[cfg(feature = "tcod")]
+const PANEL_HEIGHT: i32 = 7;
+#[cfg(feature = "tcod")]
+const PANEL_Y: i32 = SCREEN_HEIGHT - PANEL_HEIGHT;
+
+// Mensagens
+#[cfg(feature = "tcod")]
+const MSG_X: i32 = BAR_WIDTH + 2;
+#[cfg(feature = "tcod")]
+const MSG_WIDTH: i32 = SCREEN_WIDTH - BAR_WIDTH - 2;
+#[cfg(feature = "tcod")]
+const MSG_HEIGHT: usize = PANEL_HEIGHT as usize - 1;
+
+//parameters for dungeon generator
+const ROOM_MAX_SIZE: i32 = 30;
+const ROOM_MIN_SIZE: i32 = 5;
+
+// how often each shape is picked for a room, the L-shape gets a random missing quarter
+const ROOM_SHAPE_WEIGHTS: [(enums::RoomShape, u32); 5] = [
+    (enums::RoomShape::Rectangle, 40),
+    (enums::RoomShape::Circle, 15),
+    (enums::RoomShape::Cross, 15),
+    (enums::RoomShape::LShape(0), 15),
+    (enums::RoomShape::PillaredHall, 15),
+];
+// pillared halls get a pillar every this many tiles
+const PILLAR_SPACING: i32 = 3;
+
+//parameters for the binary space partition layout
+const BSP_MAX_LEAF_SIZE: i32 = 50;
+const BSP_MIN_LEAF_SIZE: i32 = ROOM_MIN_SIZE + 2;
+const BSP_CONNECTION_PENALTY: i32 = 10;
+
+//parameters for the corridors between rooms
+const CORRIDOR_DEFAULT_WIDTH: i32 = 2;
+const CORRIDOR_MAX_WIDTH: i32 = 3;
+// costs of the winding corridor search, digging through rock is dearer than
+// walking through space that is already open, and the noise makes it wander
+const CORRIDOR_OPEN_COST: i32 = 1;
+const CORRIDOR_WALL_COST: i32 = 4;
+const CORRIDOR_WINDING: f64 = 6.0;
+const CORRIDOR_NOISE_SCALE: f64 = 0.15;
+// what a step usually costs, halfway between open space and rock plus the average winding.
+// Aiming the search with it gives up the cheapest path for a much shorter search
+const CORRIDOR_EXPECTED_COST: i32 = 5;
+// how far past the two ends the search may stray, to get around a vault for instance
+const CORRIDOR_SEARCH_MARGIN: i32 = 12;
+
+// chance of a room being replaced by a hand-authored prefab
+const PREFAB_CHANCE: f32 = 0.08;
+// levels generated from seeds derived from the level's own before giving up on it
+const LEVEL_ATTEMPTS: usize = 10;
+// spots tried for a vault in the overlapping layout before a plain room goes there instead
+const PREFAB_PLACEMENT_TRIES: i32 = 10;
+
+// doors
+const DOOR_CHANCE: f32 = 0.7;
+// as wide as the widest corridor, wider gaps are where rooms overlap
+const MAX_DOOR_WIDTH: usize = CORRIDOR_MAX_WIDTH as usize;
+// chance of a door being hidden, narrow ones become secret doors and wide ones secret passages
+const SECRET_DOOR_CHANCE: f32 = 0.15;
+
+// traps
+#[cfg(feature = "tcod")]
+const DART_DAMAGE: i32 = 4;
+#[cfg(feature = "tcod")]
+const ALARM_RADIUS: f32 = 25.0;
+#[cfg(feature = "tcod")]
+const ALARM_TURNS: i32 = 40;
+#[cfg(feature = "tcod")]
+const GAS_TURNS: i32 = 6;
+#[cfg(feature = "tcod")]
+const DISARM_CHANCE: f32 = 0.6;
+
+// searching
+#[cfg(feature = "tcod")]
+const SEARCH_TURNS: i32 = 3;
+// chance of finding each hidden tile or trap next to the player, rolled once per turn spent
+#[cfg(feature = "tcod")]
+const SEARCH_CHANCE: f32 = 0.35;
+
+//parameters for the noise map generator
+const NOISE_SCALE: f64 = 24.0;
+const NOISE_OCTAVES: u32 = 4;
+const NOISE_PERSISTENCE: f64 = 0.5;
+const NOISE_CONTRAST: f64 = 2.2;
+const NOISE_REGION_SIZE: i32 = 20;
+// random tiles tried for the player's start before a floor is carved for it
+const NOISE_START_TRIES: i32 = 1000;
+
+//parameters for the cellular automata cave generator
+const CAVE_FILL_PROBABILITY: f32 = 0.45;
+const CAVE_SMOOTHING_PASSES: i32 = 5;
+const CAVE_WALL_THRESHOLD: i32 = 5;
+
+//parameters for the maze generator
+const MAZE_BRAID: f32 = 0.0;  // default chance of opening up a dead end, 0 keeps the maze perfect
+const MAZE_CHAMBER_DENSITY: i32 = 1500;  // one chamber for every this many maze cells
+const MAZE_CHAMBER_MIN_CELLS: i32 = 2;
+const MAZE_CHAMBER_MAX_CELLS: i32 = 4;
+const MAZE_DIRECTIONS: [(i32, i32); 4] = [(-1, 0), (1, 0), (0, -1), (0, 1)];
+
+//parameters for the rivers and lakes of the rooms generator
+const RIVER_DENSITY: i32 = 400;  // one river for every this many tiles of width plus height
+const RIVER_STEP: i32 = 8;  // tiles between the waypoints the river bends at
+const RIVER_MEANDER: f64 = 12.0;  // how far a waypoint can stray from the straight line
+const RIVER_WIDTH: i32 = 2;  // tiles of shallow water on each side of the deep middle
+const LAKE_DENSITY: i32 = 40000;  // one lake for every this many tiles of area
+const LAKE_MIN_RADIUS: i32 = 3;
+const LAKE_MAX_RADIUS: i32 = 9;
+
+#[cfg(feature = "tcod")]
+const INVENTORY_WIDTH: i32 = 50;
+
+#[cfg(feature = "tcod")]
+const FOV_ALGO: FovAlgorithm = FovAlgorithm::Shadow;  // default FOV algorithm
+#[cfg(feature = "tcod")]
+const FOV_LIGHT_WALLS: bool = true;  // light walls or not
+//const TORCH_RADIUS: i32 = 10;
+
+#[cfg(feature = "tcod")]
+const LIMIT_FPS: i32 = 20;  // 20 frames-per-second maximum
+
+const COLOR_DARK_WALL: Color =      Color { r: 26, g: 37, b: 47 };
+const COLOR_LIGHT_WALL: Color =     Color { r: 44, g: 62, b: 80 };
+const COLOR_DARK_GROUND: Color =    Color { r: 77, g: 23, b: 17 };
+const COLOR_LIGHT_GROUND: Color =   Color { r: 231, g: 76, b: 60 };
+const COLOR_DARK_DOOR: Color =      Color { r: 66, g: 44, b: 20 };
+const COLOR_LIGHT_DOOR: Color =     Color { r: 160, g: 110, b: 50 };
+const COLOR_DARK_BRIDGE: Color =    Color { r: 56, g: 40, b: 24 };
+const COLOR_LIGHT_BRIDGE: Color =   Color { r: 140, g: 100, b: 60 };
+#[cfg(feature = "tcod")]
+const COLOR_TARGET_RANGE: Color =   Color { r: 80, g: 120, b: 200 };
+#[cfg(feature = "tcod")]
+const COLOR_TARGET_LINE: Color =    Color { r: 255, g: 140, b: 0 };
+#[cfg(feature = "tcod")]
+const COLOR_TARGET_CURSOR: Color =  Color { r: 255, g: 255, b: 0 };
+
+/// The single random number generator every game decision is drawn from
+type GameRng = StdRng;
+
+// STRUCTS
+
+/// a floor the player has left, kept so it can be restored when coming back
+#[cfg_attr(not(feature = "tcod"), allow(dead_code))]
+struct Level {
+    map: Map,
+    objects: Vec<Object>,
+}
+
+/// how levels are generated and every floor visited so far
+pub struct Dungeon {
+    seed: u32,
+    depth: i32,
+    config: GenerationConfig,
+    spawn_tables: spawn::SpawnTables,
+    themes: theme::Themes,
+    prefabs: Vec<prefab::Prefab>,
+    levels: HashMap<i32, Level>,
+}
+
+/// how the rooms generator joins its rooms
+#[derive(Clone, Copy, Debug)]
+pub struct Corridors {
+    pub style: enums::CorridorStyle,
+    // from 1 to `CORRIDOR_MAX_WIDTH` tiles
+    pub width: i32,
+}
+
+/// the generation options picked on the command line, the same for every level
+#[derive(Clone, Copy, Debug)]
+pub struct GenerationConfig {
+    pub width: i32,
+    pub height: i32,
+    pub generator: enums::Generator,
+    pub layout: enums::RoomLayout,
+    pub corridors: Corridors,
+    // chance of a maze dead end being opened into a loop
+    pub braid: f32,
+}
+
+/// what the rooms generator fills the rooms with
+struct RoomContents<'a> {
+    table: &'a spawn::SpawnTable,
+    // the vaults some rooms are replaced with
+    prefabs: &'a [prefab::Prefab],
+}
+
+/// digs the tunnels of a level in the corridor style asked for, keeping the
+/// noise and the search buffers of the winding ones from one tunnel to the next
+struct Tunneler {
+    corridors: Corridors,
+    // made on the first winding tunnel, the straight ones never need it
+    noise: Option<(noise::Perlin, f64)>,
+    cost_so_far: Vec<i32>,
+    came_from: Vec<Option<(i32, i32)>>,
+}
+
+#[cfg(feature = "tcod")]
+struct Tcod {
+    renderer: Box<dyn Renderer>,
+    fov: FovMap,
+    mouse: Mouse,
+    camera: Camera,
+    minimap: minimap::Minimap,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+struct Fighter {
+    max_hp: i32,
+    hp: i32,
+    max_mana: i32,
+    mana: i32,
+    defense: i32,
+    power: i32,
+    on_death: enums::DeathCallback,
+}
+
+
+#[derive(Clone, Copy, Debug)]
+pub struct Tile {
+    blocked: bool,
+    // only read by the game's field of view and rendering
+    #[cfg_attr(not(feature = "tcod"), allow(dead_code))]
+    explored: bool,
+    #[cfg_attr(not(feature = "tcod"), allow(dead_code))]
+    block_sight: bool,
+    char: char,
+    light_color: Color,
+    dark_color: Color,
+    door: Option<enums::DoorState>,
+    // looks and behaves like a wall until the player finds it
+    secret: Option<enums::Secret>,
+    terrain: enums::Terrain,
+    // stamped from a prefab, so the tunnels and rooms carved after it go around it
+    vault: bool
+}
+
+#[derive(Clone, Copy, Debug)]
+struct Rect {
+    x1: i32,
+    y1: i32,
+    x2: i32,
+    y2: i32,
+    connections: i32,
+    // stamped from a prefab, so later passes leave its layout alone
+    vault: bool,
+    // the tile outside a vault's door, where tunnels reach it
+    entrance: Option<(i32, i32)>,
+    // the floor carved inside the rectangle, which is the room's outer wall
+    shape: enums::RoomShape,
+}
+
+/// what the connectivity pass found and fixed, useful when debugging the generators
+#[derive(Clone, Copy, Debug)]
+pub struct GenerationStats {
+    // 0 for the generators that don't lay out rooms
+    pub rooms: i32,
+    pub floor_tiles: i32,
+    pub water_tiles: i32,
+    // areas of passable tiles before the connectivity pass joined them
+    pub regions_found: i32,
+    pub tunnels_added: i32,
+    pub stairs_distance: Option<i32>,
+    pub monsters: i32,
+    pub items: i32,
+    // levels generated until the connectivity pass could fix one
+    pub attempts: i32,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+struct Item {
+    item_type: enums::ItemType,
+    amount: u32,
+    range: u32
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+struct Trap {
+    kind: enums::TrapKind,
+    // not drawn until the player finds it or sets it off
+    hidden: bool,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Object {
+    x: i32,
+    y: i32,
+    char: char,
+    color: Color,
+    name: String,
+    blocks: bool,
+    alive: bool,
+    fighter: Option<Fighter>,
+    ai: Option<enums::Ai>,
+    item: Option<Item>,
+    trap: Option<Trap>,
+    torch_radius: i32,
+    always_visible: bool,
+    // turns left before it can act again, after wading through slow terrain
+    wait: i32,
+    // turns the player stumbles around after breathing gas, monsters use `Ai::Confused`
+    confused: i32
+}
+
+// IMPLEMENTATIONS
+
+impl enums::DeathCallback {
+    fn callback(self, object: &mut Object, messages: &mut Messages) {
+        use enums::DeathCallback::*;
+        let callback: fn(&mut Object, &mut Messages) = match self {
+            Player => player_death,
+            Monster => monster_death,
+        };
+        callback(object, messages);
+    }
+}
+
+impl Tile {
+    pub fn new(blocked: bool, explored: bool, block_sight: bool, char: char, light_color: Color, dark_color: Color, terrain: enums::Terrain) -> Self {
+        Tile {blocked: blocked, explored: explored, block_sight: block_sight, char: char, light_color: light_color, dark_color: dark_color, door: None, secret: None, terrain: terrain, vault: false}
+    }
+    pub fn empty() -> Self {
+        Tile{blocked: false, explored: false, block_sight: false, char: '.', light_color: COLOR_LIGHT_GROUND, dark_color: COLOR_DARK_GROUND, door: None, secret: None, terrain: enums::Terrain::Floor, vault: false }
+    }
+
+    pub fn floor(char: char) -> Self {
+        Tile{blocked: false, explored: false, block_sight: false, char: char, light_color: COLOR_LIGHT_GROUND, dark_color: COLOR_DARK_GROUND, door: None, secret: None, terrain: enums::Terrain::Floor, vault: false }
+    }
+
+    pub fn wall() -> Self {
+        Tile{blocked: true, explored: false, block_sight: true, char: '#', light_color: COLOR_LIGHT_WALL, dark_color: COLOR_DARK_WALL, door: None, secret: None, terrain: enums::Terrain::Wall, vault: false }
+    }
+
+    /// a closed door blocks movement and sight, an open one does neither
+    pub fn door(state: enums::DoorState) -> Self {
+        let closed = state == enums::DoorState::Closed;
+        let char = if closed { '+' } else { '\'' };
+        Tile{blocked: closed, explored: false, block_sight: closed, char: char, light_color: COLOR_LIGHT_DOOR, dark_color: COLOR_DARK_DOOR, door: Some(state), secret: None, terrain: enums::Terrain::Floor, vault: false }
+    }
+
+    /// a wall hiding a door or a passage
+    pub fn secret(secret: enums::Secret) -> Self {
+        Tile { secret: Some(secret), ..Tile::wall() }
+    }
+
+    /// whether something can get through this tile, opening a door or finding it first if needed
+    pub fn passable(&self) -> bool {
+        !self.blocked || self.door.is_some() || self.secret.is_some()
+    }
+
+    pub fn water() -> Self {
+        Tile::new(false, false, false, '~', colors::BLUE, colors::DARK_BLUE, enums::Terrain::ShallowWater)
+    }
+
+    pub fn deep_water() -> Self {
+        Tile::new(false, false, false, '~', colors::DARK_BLUE, colors::DARKEST_BLUE, enums::Terrain::DeepWater)
+    }
+
+    /// carries a tunnel over a river or a lake
+    pub fn bridge() -> Self {
+        Tile::new(false, false, false, '=', COLOR_LIGHT_BRIDGE, COLOR_DARK_BRIDGE, enums::Terrain::Bridge)
+    }
+}
+
+impl enums::Terrain {
+    /// turns it takes to step onto this terrain
+    pub fn movement_cost(self) -> i32 {
+        use enums::Terrain::*;
+        match self {
+            Floor | Wall | Bridge => 1,
+            ShallowWater => 2,
+            DeepWater => 3,
+        }
+    }
+
+    /// power lost when attacking while standing on this terrain
+    pub fn attack_penalty(self) -> i32 {
+        use enums::Terrain::*;
+        match self {
+            Floor | Wall | Bridge => 0,
+            ShallowWater => 1,
+            DeepWater => 3,
+        }
+    }
+
+    /// items dropped here sink and are gone for good
+    pub fn swallows_items(self) -> bool {
+        self == enums::Terrain::DeepWater
+    }
+
+    pub fn is_water(self) -> bool {
+        self == enums::Terrain::ShallowWater || self == enums::Terrain::DeepWater
+    }
+}
+
+
+impl Dungeon {
+    /// a dungeon with no floor generated yet, loading the spawn tables, themes and prefabs
+    pub fn new(seed: u32, config: GenerationConfig) -> Self {
+        Dungeon {
+            seed: seed,
+            depth: 1,
+            config: config,
+            spawn_tables: spawn::SpawnTables::load(),
+            themes: theme::Themes::load(),
+            prefabs: prefab::load_prefabs(),
+            levels: HashMap::new(),
+        }
+    }
+
+    /// starts over with another seed, forgetting the floors generated with the old one
+    pub fn set_seed(&mut self, seed: u32) {
+        self.seed = seed;
+        self.depth = 1;
+        self.levels.clear();
+    }
+}
+
+impl enums::Generator {
+    /// the name used by `--generator` and the themes file
+    pub fn name(self) -> &'static str {
+        match self {
+            enums::Generator::Rooms => "rooms",
+            enums::Generator::Perlin => "perlin",
+            enums::Generator::Caves => "caves",
+            enums::Generator::Maze => "maze",
+        }
+    }
+}
+
+impl enums::MessageCategory {
+    /// how the log viewer calls it
+    pub fn name(self) -> &'static str {
+        match self {
+            enums::MessageCategory::Combat => "combate",
+            enums::MessageCategory::Item => "itens",
+            enums::MessageCategory::System => "sistema",
+        }
+    }
+}
+
+impl enums::RoomShape {
+    /// whether the room's walls follow its rectangle, so tunnel entrances on them can get doors
+    pub fn has_straight_walls(self) -> bool {
+        self == enums::RoomShape::Rectangle || self == enums::RoomShape::PillaredHall
+    }
+}
+
+impl Rect {
+    pub fn new(x: i32, y: i32, w: i32, h: i32) -> Self {
+        Rect { x1: x, y1: y, x2: x + w, y2: y + h, connections: 0, vault: false, entrance: None, shape: enums::RoomShape::Rectangle }
+    }
+
+    /// a floor tile in the middle of the room
+    pub fn center(&self) -> (i32, i32) {
+        let center_x = (self.x1 + self.x2) / 2;
+        let center_y = (self.y1 + self.y2) / 2;
+        match self.shape {
+            // the middle of the quarter opposite to the missing one, well inside the L
+            enums::RoomShape::LShape(missing) => {
+                let (left, right) = ((self.x1 + 1 + center_x) / 2, (center_x + self.x2) / 2);
+                let (top, bottom) = ((self.y1 + 1 + center_y) / 2, (center_y + self.y2) / 2);
+                match missing {
+                    0 => (right, bottom),
+                    1 => (left, bottom),
+                    2 => (left, top),
+                    _ => (right, top),
+                }
+            }
+            _ => (center_x, center_y),
+        }
+    }
+
+    /// where tunnels are hooked up: the entrance of a vault, the middle of any other room
+    pub fn hook(&self) -> (i32, i32) {
+        self.entrance.unwrap_or_else(|| self.center())
+    }
+
+    /// whether the tile is part of the room's floor
+    pub fn contains(&self, x: i32, y: i32) -> bool {
+        if x <= self.x1 || x >= self.x2 || y <= self.y1 || y >= self.y2 {
+            return false;
+        }
+        let (center_x, center_y) = ((self.x1 + self.x2) / 2, (self.y1 + self.y2) / 2);
+        let (dx, dy) = (x - center_x, y - center_y);
+        let (w, h) = (self.x2 - self.x1, self.y2 - self.y1);
+        match self.shape {
+            enums::RoomShape::Rectangle => true,
+            enums::RoomShape::Circle => {
+                let radius = cmp::min(w, h) / 2 - 1;
+                // the extra radius rounds off the single tiles sticking out of the sides
+                dx * dx + dy * dy <= radius * radius + radius
+            }
+            enums::RoomShape::Cross => dx.abs() <= w / 6 || dy.abs() <= h / 6,
+            // the middle row and column always stay, so both arms of the L touch
+            enums::RoomShape::LShape(missing) => match missing {
+                0 => !(dx < 0 && dy < 0),
+                1 => !(dx > 0 && dy < 0),
+                2 => !(dx > 0 && dy > 0),
+                _ => !(dx < 0 && dy > 0),
+            },
+            // single pillars away from the walls and off the middle row and column,
+            // so they never cut the hall in two nor block a tunnel
+            enums::RoomShape::PillaredHall => {
+                let away_from_walls = x > self.x1 + 1 && x < self.x2 - 1 && y > self.y1 + 1 && y < self.y2 - 1;
+                let pillar = dx.abs() % PILLAR_SPACING == PILLAR_SPACING - 1 &&
+                    dy.abs() % PILLAR_SPACING == PILLAR_SPACING - 1;
+                !(away_from_walls && pillar)
+            }
+        }
+    }
+
+    pub fn intersects_with(&self, other: &Rect) -> bool {
+        // returns true if this rectangle intersects with another one
+        (self.x1 <= other.x2) && (self.x2 >= other.x1) &&
+            (self.y1 <= other.y2) && (self.y2 >= other.y1)
+    }
+}
+
+impl Item {
+    pub fn new(item_type: enums::ItemType, amount: u32, range: u32) -> Self {
+        Item {
+            item_type: item_type,
+            amount: amount,
+            range: range
+        }
+    }
+
+    pub fn heal_potion() -> Self {
+        Item::new(enums::ItemType::Heal, 5, 0)
+    }
+
+    pub fn fire_ball() -> Self {
+        Item::new(enums::ItemType::FireBolt, 20, 5)
+    }
+
+    pub fn confusion_spell() -> Self {
+        Item::new(enums::ItemType::Confuse, 0, 5)
+    }
+
+    pub fn fear_spell() -> Self {
+       Item::new(enums::ItemType::Scare, 0, 5)
+    }
+
+    pub fn merge_spell() -> Self {
+       Item::new(enums::ItemType::Merge, 0, 5)
+    }
+    
+}
+
+impl Object {
+    pub fn player(x: i32, y: i32) -> Self {
+        let mut player = Object::new(x, y, '@', "Player".into(), colors::WHITE, true);
+        player.alive = true;
+        player.fighter = Some(Fighter {
+            mana: 15,
+            max_mana: 15,
+            max_hp: 30,
+            hp: 30, 
+            defense: 2,
+            power: 5,
+            on_death: enums::DeathCallback::Player
+        });
+        player
+    }
+
+    pub fn new(x: i32, y: i32, char: char, name: String, color: Color, blocks: bool) -> Self {
+        Object {
+            x: x,
+            y: y,
+            char: char,
+            color: color,
+            name: name,
+            blocks: blocks,
+            alive: false,
+            fighter: None,
+            ai: None,
+            item: None,
+            trap: None,
+            torch_radius: 10,
+            always_visible: false,
+            wait: 0,
+            confused: 0
+        }
+    }
+
+    pub fn trap(x: i32, y: i32, kind: enums::TrapKind) -> Self {
+        let (name, color) = match kind {
+            enums::TrapKind::Dart => ("armadilha de dardos", colors::LIGHT_RED),
+            enums::TrapKind::Teleport => ("armadilha de teleporte", colors::LIGHT_MAGENTA),
+            enums::TrapKind::Alarm => ("alarme", colors::LIGHT_YELLOW),
+            enums::TrapKind::ConfusionGas => ("armadilha de gas", colors::LIGHT_GREEN),
+            enums::TrapKind::Pit => ("buraco", colors::DARK_SEPIA),
+        };
+        let mut trap = Object::new(x, y, '^', name.into(), color, false);
+        trap.trap = Some(Trap { kind: kind, hidden: true });
+        trap
+    }
+
+    pub fn duergar(x: i32, y: i32) -> Self {
+        let mut orc = Object::new(x, y, 'd', "Duergar".into() , colors::GREY, true);
+        orc.fighter = Some(Fighter{
+            mana: 0,
+            max_mana: 0,
+            max_hp: 10, 
+            hp: 10, 
+            defense: 0,
+            power: 2,
+            on_death: enums::DeathCallback::Monster
+        });
+        orc.ai = Some(enums::Ai::Basic);
+        orc
+    }
+
+    pub fn troll(x: i32, y: i32) -> Self {
+        let mut troll = Object::new(x, y, 'T', "Troll".into() , colors::Color {
+            r: 39,
+            g: 174,
+            b: 96
+        }, true);
+        troll.fighter = Some(Fighter {
+            mana: 0,
+            max_mana: 0,
+            max_hp: 15, 
+            hp: 15, 
+            defense: 1, 
+            power: 4,
+            on_death: enums::DeathCallback::Monster
+        });
+        troll.ai = Some(enums::Ai::Basic);
+        troll
+    }
+
+    pub fn goblin(x: i32, y: i32) -> Self {
+        let mut brenda = Object::new(x,y, 'g', "Goblin".into(), colors::GREEN, true);
+        brenda.fighter = Some(Fighter{
+            mana: 0,
+            max_mana: 0,
+            max_hp: 12, 
+            hp: 12, 
+            defense: 1, 
+            power: 3,
+            on_death: enums::DeathCallback::Monster
+        });
+        brenda.ai = Some(enums::Ai::Basic);
+        brenda
+    }
+
+    pub fn heal_potion(x: i32, y: i32) -> Self {
+         let mut object = Object::new(x, y, '!', "Pocao de cura".to_string(), colors::Color {
+            r: 142,
+            g: 68,
+            b: 173
+        }, false);
+        object.item = Some(Item::heal_potion());
+        object
+    }
+
+    pub fn fire_ball(x: i32, y: i32) -> Self {
+        let mut object = Object::new(x, y, 'º', "Bola de Fogo".to_string(), colors::LIGHT_RED, false);
+        object.item = Some(Item::fire_ball());
+        object
+    }
+
+    pub fn confusion_spell(x: i32, y: i32) -> Self {
+        let mut object = Object::new(x, y, '$', "Feitico de confusao".to_string(), colors::Color {
+            r: 211,
+            g: 84,
+            b: 0
+        }, false);
+        object.item = Some(Item::confusion_spell());
+        object
+    }
+
+    pub fn fear_spell(x: i32, y: i32) -> Self {
+        let mut object = Object::new(x, y, '*', "Feitico de medo".to_string(), colors::BLACK, false);
+        object.item = Some(Item::fear_spell());
+        object
+    }
+
+    pub fn merge_spell(x: i32, y: i32) -> Self {
+        let mut object = Object::new(x, y, 'M', "Feitico de fusao".into(), colors::ORANGE, false);
+        object.item = Some(Item::merge_spell());
+        object
+    }
+     
+    /// draw the character that represents this object where the camera shows its position
+    #[cfg(feature = "tcod")]
+    fn draw(&self, renderer: &mut dyn Renderer, camera: &Camera) {
+        if let Some((x, y)) = camera.to_screen(self.x, self.y) {
+            renderer.put_char(x, y, self.char, self.color);
+        }
+    }
+
+    pub fn pos(&self) -> (i32, i32) {
+        (self.x, self.y)
+    }
+    pub fn set_pos(&mut self, x: i32, y: i32) {
+        self.x = x;
+        self.y = y;
+    }
+
+    pub fn distance_to(&self, other: &Object) -> f32 {
+        let dx = other.x - self.x;
+        let dy = other.y - self.y;
+        ((dx.pow(2) + dy.pow(2)) as f32).sqrt()
+    }
+
+    pub fn distance(&self, x: i32, y: i32) -> f32 {
+        (((x - self.x).pow(2) + (y - self.y).pow(2)) as f32).sqrt()
+    }
+
+    pub fn heal(&mut self, amount: i32) {
+        if let Some(fighter) = self.fighter.as_mut() {
+            fighter.hp += amount;
+            if fighter.hp > fighter.max_hp {
+                fighter.hp = fighter.max_hp;
+            }
+        }
+    }
+
+    pub fn take_mana(&mut self, amount: i32) {
+        if let Some(fighter) = self.fighter.as_mut() {
+            fighter.mana -= amount;
+            if fighter.mana < 1 {
+                fighter.mana = 0;
+            }
+        }
+    }
+
+    pub fn take_damage(&mut self, damage: i32, messages: &mut Messages) {
+        // apply damage if possible
+        if let Some(fighter) = self.fighter.as_mut() {
+            if damage > 0 {
+                fighter.hp -= damage;
+            }
+            else {
+                fighter.hp = 0;
+            }
+        }
+
+        if let Some(fighter) = self.fighter {
+            if fighter.hp <= 0 {
+                self.alive = false;
+                fighter.on_death.callback(self, messages);
+            }
+        }
+    }
+
+    pub fn attack(&mut self, target: &mut Object, footing: enums::Terrain, messages: &mut Messages) {
+    // a simple formula for attack damage, weakened when fighting from water
+        let damage = self.fighter.map_or(0, |f| f.power) - footing.attack_penalty() - target.fighter.map_or(0, |f| f.defense);
+        if damage > 0 {
+            // make the target take some damage
+            message(messages, format!("{} ataca {} com {} pontos de forca.", self.name, target.name, damage), colors::RED, enums::MessageCategory::Combat);
+            target.take_damage(damage, messages);
+        } else {
+            message(messages, format!("{} ataca {} mas nao tem efeito!", self.name, target.name), colors::RED, enums::MessageCategory::Combat);
+        }
+    }
+
+    pub fn merge(&mut self, target: &mut Object) -> Object {
+        let defense = ( ((self.fighter.map_or(0, |f| f.defense) + target.fighter.map_or(0, |f| f.defense)) as f32) * 0.75) as i32;
+        let power = (((self.fighter.map_or(0, |f| f.power) + target.fighter.map_or(0, |f| f.power)) as f32) * 0.75)  as i32;
+        let max_hp = (((self.fighter.map_or(0, |f| f.max_hp) + target.fighter.map_or(0, |f| f.max_hp)) as f32) * 0.75)  as i32; 
+        let hp = std::cmp::min(self.fighter.map_or(0, |f| f.hp) + target.fighter.map_or(0, |f| f.hp), max_hp);
+
+        let c = self.char;
+        let color = target.color;
+        let name = format!("{} {}", self.name, "");
+
+        let mut object = Object::new(self.x, self.y, c, name, color, self.blocks);
+        object.fighter = Some(Fighter {
+            mana: self.fighter.map_or(0, |f| f.mana),
+            max_mana: self.fighter.map_or(0, |f| f.max_mana),
+            defense: defense,
+            hp: hp,
+            max_hp: max_hp,
+            power: power,
+            on_death: enums::DeathCallback::Player
+        });
+
+        object.ai = self.ai.clone();
+        object.alive = true;
+        
+        object
+    }
+
+}
+
+// FUNTIONS
+
+#[cfg(feature = "tcod")]
+fn pick_item_up(object_id: usize, objects: &mut Vec<Object>, inventory: &mut Vec<Object>,
+                messages: &mut Messages) {
+    if inventory.len() >= 26 {
+        message(messages,
+                format!("Inventario cheio. Nao pode pegar {}.", objects[object_id].name),
+                colors::COPPER, enums::MessageCategory::Item);
+    } else {
+        let item = objects.swap_remove(object_id);
+        message(messages, format!("Voce pegou {}!", item.name), colors::DARK_GREEN, enums::MessageCategory::Item);
+        inventory.push(item);
+    }
+}
+
+#[cfg(feature = "tcod")]
+fn drop_item(inventory_id: usize, inventory: &mut Vec<Object>, objects: &mut Vec<Object>, map: &Map,
+             messages: &mut Messages) {
+    let mut item = inventory.remove(inventory_id);
+    let (x, y) = objects[PLAYER].pos();
+    if map[(x, y)].terrain.swallows_items() {
+        message(messages, format!("{} afunda na agua e se perde.", item.name), colors::LIGHT_BLUE, enums::MessageCategory::Item);
+        return;
+    }
+    item.set_pos(x, y);
+    message(messages, format!("Voce largou {}.", item.name), colors::YELLOW, enums::MessageCategory::Item);
+    objects.push(item);
+}
+
+fn player_death(player: &mut Object, messages: &mut Messages) {
+    // the game ended!
+    message(messages, "Voce morreu!", colors::RED, enums::MessageCategory::Combat);
+
+    // for added effect, transform the player into a corpse!
+    player.char = '%';
+    player.color = colors::DARK_RED;
+}
+
+fn monster_death(monster: &mut Object, messages: &mut Messages) {
+    // transform it into a nasty corpse! it doesn't block, can't be
+    // attacked and doesn't move
+    message(messages, format!("{} esta morto", monster.name), colors::GREEN, enums::MessageCategory::Combat);
+    monster.char = 'x';
+    monster.color = colors::DARK_RED;
+    monster.blocks = false;
+    monster.fighter = None;
+    monster.ai = None;
+    monster.name = format!("Restos mortais de {}", monster.name);
+}
+
+#[cfg(feature = "tcod")]
+fn mut_two<T>(first_index: usize, second_index: usize, items: &mut [T]) -> (&mut T, &mut T) {
+    assert!(first_index != second_index);
+    let split_at_index = cmp::max(first_index, second_index);
+    let (first_slice, second_slice) = items.split_at_mut(split_at_index);
+    if first_index < second_index {
+        (&mut first_slice[first_index], &mut second_slice[0])
+    } else {
+        (&mut second_slice[0], &mut first_slice[second_index])
+    }
+}
+
+#[cfg(feature = "tcod")]
+fn ai_take_turn(monster_id: usize, map: &Map, objects: &mut [Object], fov_map: &FovMap, messages: &mut Messages, rng: &mut GameRng) {
+    use enums::Ai::*;
+    if objects[monster_id].wait > 0 {
+        // still wading through slow terrain
+        objects[monster_id].wait -= 1;
+        return;
+    }
+    if let Some(ai) = objects[monster_id].ai.take() {
+        let new_ai = match ai {
+            Basic =>                        ai_basic(monster_id, map, objects, fov_map, messages, rng),
+            Confused{previous, turns} =>    ai_confused(monster_id, map, objects, messages, previous, turns, rng),
+            Scared{previous, turns} =>      ai_scared(monster_id, map, objects, fov_map, messages, previous, turns, rng),
+            Alerted{previous, turns} =>     ai_alerted(monster_id, map, objects, messages, previous, turns, rng),
+            Fallen =>                       Fallen
+        };
+        objects[monster_id].ai = Some(new_ai);
+    }
+}
+
+#[cfg(feature = "tcod")]
+fn ai_basic(monster_id: usize, map: &Map, objects: &mut [Object], fov_map: &FovMap, messages: &mut Messages, rng: &mut GameRng) -> enums::Ai {
+    
+    let (monster_x, monster_y) = objects[monster_id].pos();
+
+
+    if fov_map.is_in_fov(monster_x, monster_y) {
+        chase_player(monster_id, map, objects, messages, rng);
+    }
+    enums::Ai::Basic
+}
+
+#[cfg(feature = "tcod")]
+fn chase_player(monster_id: usize, map: &Map, objects: &mut [Object], messages: &mut Messages, rng: &mut GameRng) {
+    let (monster_x, monster_y) = objects[monster_id].pos();
+    if objects[monster_id].distance_to(&objects[PLAYER]) >= 2.0 {
+        // move towards player if far away
+        let (player_x, player_y) = objects[PLAYER].pos();
+        move_towards(monster_id, player_x, player_y, map, objects, messages, rng);
+        //move_away(monster_id, player_x, player_y, map, objects);
+    } else if objects[PLAYER].fighter.is_some_and(|f| f.hp > 0) {
+        // close enough, attack! (if the player is still alive.)
+        let footing = map[(monster_x, monster_y)].terrain;
+        let (monster, player) = mut_two(monster_id, PLAYER, objects);
+        monster.attack(player, footing, messages);
+    }
+}
+
+#[cfg(feature = "tcod")]
+fn ai_alerted(monster_id: usize, map: &Map, objects: &mut [Object], messages: &mut Messages,
+              previous: Box<enums::Ai>, turns: i32, rng: &mut GameRng) -> enums::Ai {
+    if turns > 0 {
+        chase_player(monster_id, map, objects, messages, rng);
+        enums::Ai::Alerted{previous: previous, turns: turns - 1}
+    } else {
+        *previous
+    }
+}
+
+#[cfg(feature = "tcod")]
+fn ai_scared(monster_id: usize, map: &Map, objects: &mut [Object], fov_map: &FovMap, messages: &mut Messages, previous: Box<enums::Ai>, turns: i32, rng: &mut GameRng) -> enums::Ai {
+    
+    if turns > 0 {
+        let (monster_x, monster_y) = objects[monster_id].pos();
+        if fov_map.is_in_fov(monster_x, monster_y) {
+            
+            let (player_x, player_y) = objects[PLAYER].pos();
+            move_away(monster_id, player_x, player_y, map, objects, messages, rng);
+            return enums::Ai::Scared{previous: previous, turns: turns - 1};
+        }
+    }
+    *previous
+}
+
+
+#[cfg(feature = "tcod")]
+fn ai_confused(monster_id: usize, map: &Map, objects: &mut [Object], messages: &mut Messages,
+               previous: Box<enums::Ai>, turns: i32, rng: &mut GameRng) -> enums::Ai {
+    if turns >= 0 { 
+        let (dx, dy) = (rng.gen_range(-1, 2), rng.gen_range(-1, 2));
+        move_by(monster_id,
+                dx,
+                dy,
+                map,
+                objects,
+                messages,
+                rng);
+        enums::Ai::Confused{previous: previous, turns: turns - 1}
+    } else {  // restore the previous AI (this one will be deleted)
+        message(messages, format!("{} nao esta mais confuso",
+                                  objects[monster_id].name),
+                colors::RED, enums::MessageCategory::Combat);
+        *previous
+    }
+}
+
+
+#[cfg(feature = "tcod")]
+fn move_by(id: usize, dx: i32, dy: i32, map: &Map, objects: &mut [Object], messages: &mut Messages, rng: &mut GameRng) {
+    let (x, y) = objects[id].pos();
+    if !is_blocked(x + dx, y + dy, map, objects) {
+        objects[id].set_pos(x + dx, y + dy);
+        objects[id].wait = map[(x + dx, y + dy)].terrain.movement_cost() - 1;
+
+        let trap_id = objects.iter().position(|object| object.trap.is_some() && object.pos() == (x + dx, y + dy));
+        if let Some(trap_id) = trap_id {
+            trigger_trap(trap_id, id, map, objects, messages, rng);
+        }
+    }
+}
+
+/// sets off the trap under `victim_id`, which also gives its position away
+#[cfg(feature = "tcod")]
+fn trigger_trap(trap_id: usize, victim_id: usize, map: &Map, objects: &mut [Object], messages: &mut Messages, rng: &mut GameRng) {
+    let kind = match objects[trap_id].trap {
+        Some(trap) => trap.kind,
+        None => return,
+    };
+    reveal_trap(trap_id, objects);
+    let victim = if victim_id == PLAYER { "Voce".to_string() } else { objects[victim_id].name.clone() };
+
+    match kind {
+        enums::TrapKind::Dart => {
+            message(messages, format!("{} e atingido por um dardo!", victim), colors::LIGHT_RED, enums::MessageCategory::Combat);
+            objects[victim_id].take_damage(DART_DAMAGE, messages);
+        }
+        enums::TrapKind::Teleport => {
+            message(messages, format!("{} some num clarao!", victim), colors::LIGHT_MAGENTA, enums::MessageCategory::Combat);
+            // only somewhere the victim could have walked to, never into a sealed pocket
+            let (trap_x, trap_y) = objects[trap_id].pos();
+            let distances = walk_distances(trap_x, trap_y, map);
+            let landings: Vec<(i32, i32)> = map.positions()
+                .filter(|&(x, y)| {
+                    distances[x as usize][y as usize].is_some() && !is_blocked(x, y, map, objects) &&
+                        map[(x, y)].terrain != enums::Terrain::DeepWater
+                })
+                .collect();
+            if !landings.is_empty() {
+                let (x, y) = landings[rng.gen_range(0, landings.len())];
+                objects[victim_id].set_pos(x, y);
+            }
+        }
+        enums::TrapKind::Alarm => {
+            message(messages, "Um alarme ecoa pelos corredores!", colors::LIGHT_YELLOW, enums::MessageCategory::Combat);
+            let (trap_x, trap_y) = objects[trap_id].pos();
+            for monster in objects.iter_mut().filter(|object| object.distance(trap_x, trap_y) <= ALARM_RADIUS) {
+                if monster.ai == Some(enums::Ai::Basic) {
+                    monster.ai = Some(enums::Ai::Alerted { previous: Box::new(enums::Ai::Basic), turns: ALARM_TURNS });
+                }
+            }
+        }
+        enums::TrapKind::ConfusionGas => {
+            message(messages, format!("{} respira um gas estranho!", victim), colors::LIGHT_GREEN, enums::MessageCategory::Combat);
+            if victim_id == PLAYER {
+                objects[PLAYER].confused = GAS_TURNS;
+            } else if let Some(old_ai) = objects[victim_id].ai.take() {
+                objects[victim_id].ai = Some(enums::Ai::Confused { previous: Box::new(old_ai), turns: GAS_TURNS });
+            }
+        }
+        enums::TrapKind::Pit => {
+            message(messages, format!("{} cai num buraco!", victim), colors::LIGHT_RED, enums::MessageCategory::Combat);
+            // the player's fall is handled by `player_step`, which can change the level
+            if victim_id != PLAYER {
+                objects[victim_id].ai = Some(enums::Ai::Fallen);
+            }
+        }
+    }
+}
+
+#[cfg(feature = "tcod")]
+fn reveal_trap(trap_id: usize, objects: &mut [Object]) {
+    if let Some(trap) = objects[trap_id].trap.as_mut() {
+        trap.hidden = false;
+    }
+    // like the stairs, a known trap stays on the map once it is out of view
+    objects[trap_id].always_visible = true;
+}
+
+fn create_water(room: Rect, map: &mut Map, rng: &mut GameRng) {
+
+    let w = rng.gen_range(0, (room.x2 - room.x1).abs() / 2) ;
+    let h = rng.gen_range(0, (room.y2 - room.y1).abs() / 2);
+    // random position without going out of the boundaries of the map
+    let pos_x = rng.gen_range(room.x1 + 1, room.x2 - 1);
+    let pos_z = rng.gen_range(room.y1 + 1, room.y2 - 1);
+
+    let water = Rect::new(pos_x, pos_z, w, h);
+    for x in (water.x1 + 1)..water.x2 {
+        for y in (water.y1 + 1)..water.y2 {
+            // the pond can reach past a round room or the missing part of an L, or into a vault
+            if !room.contains(x, y) || map[(x, y)].vault {
+                continue;
+            }
+            // shallow along the shore, deep in the middle
+            let shore = cmp::min(cmp::min(x - water.x1, water.x2 - x), cmp::min(y - water.y1, water.y2 - y));
+            map[(x, y)] = if shore > 2 { Tile::deep_water() } else { Tile::water() };
+        }
+    }
+}
+
+fn random_room_shape(rng: &mut GameRng) -> enums::RoomShape {
+    let total: u32 = ROOM_SHAPE_WEIGHTS.iter().map(|&(_, weight)| weight).sum();
+    let mut roll = rng.gen_range(0, total);
+    for &(shape, weight) in &ROOM_SHAPE_WEIGHTS {
+        if roll < weight {
+            return match shape {
+                enums::RoomShape::LShape(_) => enums::RoomShape::LShape(rng.gen_range(0, 4)),
+                _ => shape,
+            };
+        }
+        roll -= weight;
+    }
+    enums::RoomShape::Rectangle
+}
+
+/// runs winding rivers from one edge of the map to the other and scatters lakes,
+/// over the open ground only, putting bridges where they cross a tunnel
+fn create_rivers_and_lakes(map: &mut Map, rooms: &[Rect], objects: &mut Vec<Object>, rng: &mut GameRng) {
+    let (width, height) = (map.width(), map.height());
+    let noise = noise::Perlin::new(rng);
+
+    for _ in 0..cmp::max(1, (width + height) / RIVER_DENSITY) {
+        let horizontal = rng.gen::<bool>();
+        let (start, end) = if horizontal {
+            ((1, rng.gen_range(1, height - 1)), (width - 2, rng.gen_range(1, height - 1)))
+        } else {
+            ((rng.gen_range(1, width - 1), 1), (rng.gen_range(1, width - 1), height - 2))
+        };
+
+        // waypoints along the straight line, pushed sideways by the noise so the river winds
+        let z = rng.gen_range(0.0, 256.0);
+        let length = cmp::max((end.0 - start.0).abs(), (end.1 - start.1).abs());
+        let steps = cmp::max(1, length / RIVER_STEP);
+        let waypoints: Vec<(i32, i32)> = (0..(steps + 1)).map(|i| {
+            let t = i as f32 / steps as f32;
+            let x = start.0 + ((end.0 - start.0) as f32 * t) as i32;
+            let y = start.1 + ((end.1 - start.1) as f32 * t) as i32;
+            let offset = ((noise.octaves(i as f64 * 0.3, z, 0.0, 3, 0.5) - 0.5) * 2.0 * RIVER_MEANDER) as i32;
+            if horizontal {
+                (x, cmp::max(1, cmp::min(height - 2, y + offset)))
+            } else {
+                (cmp::max(1, cmp::min(width - 2, x + offset)), y)
+            }
+        }).collect();
+
+        for pair in waypoints.windows(2) {
+            let ((x1, y1), (x2, y2)) = (pair[0], pair[1]);
+            for (x, y) in Bresenham::new((x1 as isize, y1 as isize), (x2 as isize, y2 as isize)) {
+                let (x, y) = (x as i32, y as i32);
+                for dx in -RIVER_WIDTH..(RIVER_WIDTH + 1) {
+                    for dy in -RIVER_WIDTH..(RIVER_WIDTH + 1) {
+                        let deep = dx.abs() < RIVER_WIDTH && dy.abs() < RIVER_WIDTH;
+                        flood_tile(x + dx, y + dy, deep, map, rooms);
+                    }
+                }
+            }
+        }
+    }
+
+    for _ in 0..cmp::max(1, width * height / LAKE_DENSITY) {
+        let radius = rng.gen_range(LAKE_MIN_RADIUS, LAKE_MAX_RADIUS + 1);
+        let (lake_x, lake_y) = (rng.gen_range(1, width - 1), rng.gen_range(1, height - 1));
+        let z = rng.gen_range(0.0, 256.0);
+        for x in (lake_x - radius)..(lake_x + radius + 1) {
+            for y in (lake_y - radius)..(lake_y + radius + 1) {
+                // the noise makes the shore ragged and the deep part off-center
+                let distance = ((((x - lake_x).pow(2) + (y - lake_y).pow(2)) as f64).sqrt() / radius as f64) +
+                    (noise.octaves(x as f64 * 0.25, y as f64 * 0.25, z, 2, 0.5) - 0.5);
+                if distance < 1.0 {
+                    flood_tile(x, y, distance < 0.55, map, rooms);
+                }
+            }
+        }
+    }
+
+    // nothing is left lying in the new water
+    objects.retain(|object| {
+        let tile = map.get(object.x, object.y);
+        let sunk = object.item.is_some() && tile.is_some_and(|tile| tile.terrain.swallows_items());
+        let drowned = object.trap.is_some() && tile.is_some_and(|tile| tile.terrain.is_water());
+        !sunk && !drowned
+    });
+}
+
+/// turns open ground into water, or into a bridge where a tunnel crosses it. Walls, the
+/// map border, vaults, doors and secret tiles are left alone, so water never opens a way
+/// through the rock or around a door
+fn flood_tile(x: i32, y: i32, deep: bool, map: &mut Map, rooms: &[Rect]) {
+    if x <= 0 || y <= 0 || x >= map.width() - 1 || y >= map.height() - 1 {
+        return;
+    }
+    let tile = map[(x, y)];
+    if tile.blocked || tile.vault || tile.door.is_some() || tile.secret.is_some() || tile.terrain == enums::Terrain::Bridge {
+        return;
+    }
+
+    let tunnel = tile.terrain == enums::Terrain::Floor && !rooms.iter().any(|room| room.contains(x, y));
+    map[(x, y)] = if tunnel {
+        Tile::bridge()
+    } else if deep || tile.terrain == enums::Terrain::DeepWater {
+        Tile::deep_water()
+    } else {
+        Tile::water()
+    };
+}
+
+/// a random prefab, randomly rotated and mirrored
+fn random_prefab(prefabs: &[prefab::Prefab], rng: &mut GameRng) -> Option<prefab::Prefab> {
+    if prefabs.is_empty() || rng.gen::<f32>() >= PREFAB_CHANCE {
+        return None;
+    }
+    let mut prefab = prefabs[rng.gen_range(0, prefabs.len())].clone();
+    for _ in 0..rng.gen_range(0, 4) {
+        prefab = prefab.rotated();
+    }
+    if rng.gen::<bool>() {
+        prefab = prefab.mirrored();
+    }
+    Some(prefab)
+}
+
+/// where the top left corner of the prefab can go in the overlapping layout, two tiles
+/// off the border so the tile outside its door isn't on it. Earlier vaults, that tile
+/// included, and the spots earlier rooms are hooked up at are left alone
+fn place_prefab(prefab: &prefab::Prefab, map: &Map, rooms: &[Rect], rng: &mut GameRng) -> Option<(i32, i32)> {
+    let (width, height) = (prefab.width(), prefab.height());
+    for _ in 0..PREFAB_PLACEMENT_TRIES {
+        let x = rng.gen_range(2, map.width() - width - 1);
+        let y = rng.gen_range(2, map.height() - height - 1);
+        let covers_vault = ((x - 1)..(x + width + 1))
+            .any(|tx| ((y - 1)..(y + height + 1)).any(|ty| map[(tx, ty)].vault));
+        let covers_hook = rooms.iter().any(|room| {
+            let (hx, hy) = room.hook();
+            hx >= x && hy >= y && hx < x + width && hy < y + height
+        });
+        if !covers_vault && !covers_hook {
+            return Some((x, y));
+        }
+    }
+    None
+}
+
+/// stamps the prefab with its top left corner at the given position, returning the room it covers.
+/// Whatever was lying where the prefab puts a wall goes away with the old layout
+fn create_prefab(prefab: &prefab::Prefab, x: i32, y: i32, objects: &mut Vec<Object>, map: &mut Map) -> Rect {
+    use prefab::PrefabCell::*;
+    let buried: Vec<usize> = ((PLAYER + 1)..objects.len())
+        .filter(|&id| {
+            let (px, py) = (objects[id].x - x, objects[id].y - y);
+            px >= 0 && py >= 0 && px < prefab.width() && py < prefab.height() && prefab.cell(px, py) == Some(&Wall)
+        })
+        .collect();
+    for id in buried.into_iter().rev() {
+        objects.remove(id);
+    }
+
+    for py in 0..prefab.height() {
+        for px in 0..prefab.width() {
+            let (tx, ty) = (x + px, y + py);
+            let cell = match prefab.cell(px, py) {
+                Some(cell) => cell,
+                None => continue,
+            };
+            map[(tx, ty)] = match *cell {
+                Wall => Tile::wall(),
+                Water => Tile::water(),
+                DeepWater => Tile::deep_water(),
+                Door => Tile::door(enums::DoorState::Closed),
+                Floor | Monster(_) | Item(_) => Tile::floor('.'),
+            };
+            map[(tx, ty)].vault = true;
+            let object = match *cell {
+                Monster(ref name) => spawn_monster(name, tx, ty),
+                Item(ref name) => spawn_item(name, tx, ty),
+                _ => continue,
+            };
+            // the names were checked when the prefab was loaded
+            if let Some(object) = object {
+                objects.push(object);
+            }
+        }
+    }
+
+    let mut room = Rect::new(x, y, prefab.width() - 1, prefab.height() - 1);
+    room.vault = true;
+    room.entrance = prefab.entrance().map(|(ex, ey)| (x + ex, y + ey));
+    room
+}
+
+fn create_room(room: &mut Rect, objects:  &mut Vec<Object>, map: &mut Map, rng: &mut GameRng, table: &spawn::SpawnTable) {
+    room.shape = random_room_shape(rng);
+
+    // go through the tiles in the rectangle and make the room's floor passable
+    for x in (room.x1 + 1)..room.x2 {
+        for y in (room.y1 + 1)..room.y2 {
+            if room.contains(x, y) && !map[(x, y)].vault {
+                map[(x, y)] = Tile::floor('.');
+            }
+        }
+    }
+
+    place_objects(*room, objects, map, rng, table);
+}
+
+/// puts doors in the gaps that tunnels leave in the walls around a room
+fn create_doors(room: &Rect, map: &mut Map, rng: &mut GameRng) {
+    let sides = [
+        (room.x1, room.y1, 1, 0, room.x2 - room.x1),
+        (room.x1, room.y2, 1, 0, room.x2 - room.x1),
+        (room.x1, room.y1, 0, 1, room.y2 - room.y1),
+        (room.x2, room.y1, 0, 1, room.y2 - room.y1),
+    ];
+    for &(start_x, start_y, dx, dy, length) in &sides {
+        // walk along the wall looking for short runs of floor between two wall tiles
+        let mut run: Vec<(i32, i32)> = vec![];
+        for i in 0..(length + 1) {
+            let (x, y) = (start_x + dx * i, start_y + dy * i);
+            let tile = match map.get(x, y) {
+                Some(&tile) if !tile.vault => tile,
+                _ => {
+                    run.clear();
+                    continue;
+                }
+            };
+            if !tile.blocked && !tile.terrain.is_water() {
+                run.push((x, y));
+                continue;
+            }
+            // a wide opening is where rooms overlap, not a tunnel entrance
+            let closed_before = run.first().is_some_and(|&(rx, ry)| {
+                let (bx, by) = (rx - dx, ry - dy);
+                map.get(bx, by).is_some_and(|tile| tile.blocked)
+            });
+            if tile.blocked && closed_before && run.len() <= MAX_DOOR_WIDTH && rng.gen::<f32>() < DOOR_CHANCE {
+                let door = if rng.gen::<f32>() >= SECRET_DOOR_CHANCE {
+                    Tile::door(enums::DoorState::Closed)
+                } else if run.len() == 1 {
+                    Tile::secret(enums::Secret::Door)
+                } else {
+                    Tile::secret(enums::Secret::Passage)
+                };
+                for &(rx, ry) in &run {
+                    map[(rx, ry)] = door;
+                }
+            }
+            run.clear();
+        }
+    }
+}
+
+/// opens or closes the door at the given position, keeping the FOV map in sync
+#[cfg(feature = "tcod")]
+fn set_door(x: i32, y: i32, state: enums::DoorState, map: &mut Map, fov_map: &mut FovMap) {
+    let explored = map[(x, y)].explored;
+    let mut door = Tile::door(state);
+    door.explored = explored;
+    map[(x, y)] = door;
+    fov_map.set(x, y, !door.block_sight, !door.blocked);
+}
+
+/// spends a few turns looking for hidden tiles and traps around the player, returns how many were found
+#[cfg(feature = "tcod")]
+fn search(map: &mut Map, objects: &mut [Object], fov_map: &mut FovMap, messages: &mut Messages, rng: &mut GameRng) -> i32 {
+    let (player_x, player_y) = objects[PLAYER].pos();
+    // the monsters get the extra turns, like when wading through water
+    objects[PLAYER].wait += SEARCH_TURNS - 1;
+
+    let mut found = 0;
+    for &(dx, dy) in &[(-1, -1), (0, -1), (1, -1), (-1, 0), (1, 0), (-1, 1), (0, 1), (1, 1)] {
+        let (x, y) = (player_x + dx, player_y + dy);
+        let secret = match map.get(x, y).and_then(|tile| tile.secret) {
+            Some(secret) => secret,
+            None => continue,
+        };
+        if (0..SEARCH_TURNS).any(|_| rng.gen::<f32>() < SEARCH_CHANCE) {
+            reveal_secret(x, y, secret, map, fov_map);
+            found += 1;
+        }
+    }
+
+    let mut traps_found = 0;
+    for trap_id in 0..objects.len() {
+        let hidden = objects[trap_id].trap.is_some_and(|trap| trap.hidden);
+        let near = (objects[trap_id].x - player_x).abs() <= 1 && (objects[trap_id].y - player_y).abs() <= 1;
+        if hidden && near && (0..SEARCH_TURNS).any(|_| rng.gen::<f32>() < SEARCH_CHANCE) {
+            reveal_trap(trap_id, objects);
+            message(messages, format!("Voce encontrou um {}!", objects[trap_id].name), colors::LIGHT_YELLOW, enums::MessageCategory::System);
+            traps_found += 1;
+        }
+    }
+
+    if found > 0 {
+        message(messages, "Voce encontrou uma passagem secreta!", colors::LIGHT_YELLOW, enums::MessageCategory::System);
+    } else if traps_found == 0 {
+        message(messages, "Voce procura, mas nao encontra nada.", colors::WHITE, enums::MessageCategory::System);
+    }
+    found + traps_found
+}
+
+/// turns a secret tile into what it was hiding, keeping the FOV map in sync
+#[cfg(feature = "tcod")]
+fn reveal_secret(x: i32, y: i32, secret: enums::Secret, map: &mut Map, fov_map: &mut FovMap) {
+    match secret {
+        enums::Secret::Door => set_door(x, y, enums::DoorState::Closed, map, fov_map),
+        enums::Secret::Passage => {
+            // borrow the look of the floor around it, so the passage matches the level's theme
+            let mut floor = map.neighbors(x, y)
+                .map(|(nx, ny)| map[(nx, ny)])
+                .find(|tile| tile.terrain == enums::Terrain::Floor && !tile.blocked && tile.door.is_none())
+                .unwrap_or(Tile::floor('.'));
+            floor.explored = map[(x, y)].explored;
+            map[(x, y)] = floor;
+            fov_map.set(x, y, !floor.block_sight, !floor.blocked);
+        }
+    }
+}
+
+/// closes every open door next to the player, returns whether any was closed
+#[cfg(feature = "tcod")]
+fn close_doors(map: &mut Map, objects: &[Object], fov_map: &mut FovMap, messages: &mut Messages) -> bool {
+    let (player_x, player_y) = objects[PLAYER].pos();
+    let mut closed = 0;
+    let mut obstructed = false;
+    for &(dx, dy) in &[(-1, 0), (1, 0), (0, -1), (0, 1)] {
+        let (x, y) = (player_x + dx, player_y + dy);
+        if map[(x, y)].door != Some(enums::DoorState::Open) {
+            continue;
+        }
+        if objects.iter().any(|object| object.pos() == (x, y)) {
+            obstructed = true;
+            continue;
+        }
+        set_door(x, y, enums::DoorState::Closed, map, fov_map);
+        closed += 1;
+    }
+
+    if closed > 0 {
+        message(messages, "Voce fecha a porta.", colors::WHITE, enums::MessageCategory::System);
+    } else if obstructed {
+        message(messages, "Algo esta bloqueando a porta.", colors::WHITE, enums::MessageCategory::System);
+    } else {
+        message(messages, "Nao ha porta aberta por perto.", colors::WHITE, enums::MessageCategory::System);
+    }
+    closed > 0
+}
+
+/// digs out the rock in a `width` by `width` square around the tile, never touching the map's
+/// border or a vault. Whatever is open already, water and doors included, stays as it is
+fn carve_corridor(x: i32, y: i32, width: i32, map: &mut Map) {
+    for dx in (-(width - 1) / 2)..(width / 2 + 1) {
+        for dy in (-(width - 1) / 2)..(width / 2 + 1) {
+            let (cx, cy) = (x + dx, y + dy);
+            let inside = cx > 0 && cy > 0 && cx < map.width() - 1 && cy < map.height() - 1;
+            if inside && !map[(cx, cy)].passable() && !map[(cx, cy)].vault {
+                map[(cx, cy)] = Tile::empty();
+            }
+        }
+    }
+}
+
+fn create_h_tunnel(x1: i32, x2: i32, y: i32, width: i32, map: &mut Map) {
+    for x in cmp::min(x1, x2)..(cmp::max(x1, x2) + 1) {
+        carve_corridor(x, y, width, map);
+    }
+}
+
+fn create_v_tunnel(y1: i32, y2: i32, x: i32, width: i32, map: &mut Map) {
+    for y in cmp::min(y1, y2)..(cmp::max(y1, y2) + 1) {
+        carve_corridor(x, y, width, map);
+    }
+}
+
+/// a straight line between the two tiles. A diagonal step also carves the tile at its
+/// corner, so even a one tile wide corridor can be walked without cutting corners
+fn create_d_tunnel(y1: i32, y2: i32, x1: i32, x2: i32, width: i32, map: &mut Map) {
+    let mut previous: Option<(i32, i32)> = None;
+    for (x, y) in Bresenham::new((x1 as isize, y1 as isize), (x2 as isize, y2 as isize)) {
+        let (x, y) = (x as i32, y as i32);
+        if let Some((px, py)) = previous {
+            if px != x && py != y {
+                carve_corridor(x, py, width, map);
+            }
+        }
+        carve_corridor(x, y, width, map);
+        previous = Some((x, y));
+    }
+}
+
+impl Tunneler {
+    fn new(corridors: Corridors) -> Self {
+        Tunneler { corridors: corridors, noise: None, cost_so_far: vec![], came_from: vec![] }
+    }
+
+    /// carves the cheapest path found by A* between the two tiles. Open space is cheaper
+    /// than rock, so the corridor reuses rooms and older corridors, and a noise field
+    /// added to the cost makes it wind instead of running straight. The search stays in
+    /// a box around the two tiles
+    fn create_winding_tunnel(&mut self, x1: i32, y1: i32, x2: i32, y2: i32, map: &mut Map, rng: &mut GameRng) {
+        if self.noise.is_none() {
+            self.noise = Some((noise::Perlin::new(rng), rng.gen_range(0.0, 256.0)));
+        }
+        let (ref noise, z) = *self.noise.as_ref().unwrap();
+
+        // the border stays solid rock
+        let (left, right) = (cmp::max(1, cmp::min(x1, x2) - CORRIDOR_SEARCH_MARGIN),
+                             cmp::min(map.width() - 2, cmp::max(x1, x2) + CORRIDOR_SEARCH_MARGIN));
+        let (top, bottom) = (cmp::max(1, cmp::min(y1, y2) - CORRIDOR_SEARCH_MARGIN),
+                             cmp::min(map.height() - 2, cmp::max(y1, y2) + CORRIDOR_SEARCH_MARGIN));
+        let height = bottom - top + 1;
+        let index = |x: i32, y: i32| ((x - left) * height + y - top) as usize;
+        let inside = |x: i32, y: i32| x >= left && x <= right && y >= top && y <= bottom;
+        if !inside(x1, y1) || !inside(x2, y2) {
+            return;
+        }
+
+        let tiles = ((right - left + 1) * height) as usize;
+        self.cost_so_far.clear();
+        self.cost_so_far.resize(tiles, i32::MAX);
+        self.came_from.clear();
+        self.came_from.resize(tiles, None);
+        let mut frontier = BinaryHeap::new();
+        self.cost_so_far[index(x1, y1)] = 0;
+        frontier.push(Reverse((0, x1, y1)));
+
+        while let Some(Reverse((_, x, y))) = frontier.pop() {
+            if (x, y) == (x2, y2) {
+                break;
+            }
+            for (nx, ny) in map.neighbors(x, y) {
+                // vaults keep their layout
+                if !inside(nx, ny) || map[(nx, ny)].vault {
+                    continue;
+                }
+                let base = if map[(nx, ny)].blocked { CORRIDOR_WALL_COST } else { CORRIDOR_OPEN_COST };
+                let wander = (noise.get(nx as f64 * CORRIDOR_NOISE_SCALE, ny as f64 * CORRIDOR_NOISE_SCALE, z) + 1.0) / 2.0;
+                let cost = self.cost_so_far[index(x, y)] + base + (wander.max(0.0) * CORRIDOR_WINDING) as i32;
+                if cost < self.cost_so_far[index(nx, ny)] {
+                    self.cost_so_far[index(nx, ny)] = cost;
+                    self.came_from[index(nx, ny)] = Some((x, y));
+                    let estimate = ((nx - x2).abs() + (ny - y2).abs()) * CORRIDOR_EXPECTED_COST;
+                    frontier.push(Reverse((cost + estimate, nx, ny)));
+                }
+            }
+        }
+
+        // walk back from the end, a target that couldn't be reached is left to the connectivity pass
+        if self.cost_so_far[index(x2, y2)] == i32::MAX {
+            return;
+        }
+        let mut current = Some((x2, y2));
+        while let Some((x, y)) = current {
+            carve_corridor(x, y, self.corridors.width, map);
+            current = self.came_from[index(x, y)];
+        }
+    }
+}
+
+fn make_perlin_map(width: i32, height: i32, objects: &mut Vec<Object>, rng: &mut GameRng, table: &spawn::SpawnTable) -> Map {
+    let mut map = Map::new(width, height, Tile::wall());
+    let noise = noise::Perlin::new(rng);
+    // a random slice of the noise volume, so every level looks different
+    let z = rng.gen_range(0.0, 256.0);
+
+    // keep a solid border so nothing can walk off the map
+    for i in 1..(height - 1) {
+        for j in 1..(width - 1)  {
+            let x = (j as f64) / NOISE_SCALE;
+            let y = (i as f64) / NOISE_SCALE;
+
+            let n = noise.octaves(x, y, z, NOISE_OCTAVES, NOISE_PERSISTENCE);
+            // octave sums cluster around 0.5, stretch them so every band below shows up
+            let n = (0.5 + (n - 0.5) * NOISE_CONTRAST).clamp(0.0, 1.0);
+
+            if n < 0.22 {
+                map[(j, i)] = Tile::deep_water()
+            }
+            else if n < 0.35 {
+                map[(j, i)] = Tile::water()
+            }
+            else if n < 0.6 {
+                map[(j, i)]  = Tile::floor('.')
+            }
+            else if n < 0.8 {
+                map[(j, i)] = Tile::floor('-')
+            }
+            else {
+                map[(j, i)]  = Tile::wall()
+            }
+        }
+    }
+
+    // the player starts on a random open tile, or in the middle of the map when the
+    // noise left too little floor to find one
+    let start = (0..NOISE_START_TRIES)
+        .map(|_| (rng.gen_range(1, width - 1), rng.gen_range(1, height - 1)))
+        .find(|&(x, y)| map[(x, y)].terrain == enums::Terrain::Floor);
+    let (player_x, player_y) = match start {
+        Some(start) => start,
+        None => {
+            let (x, y) = (width / 2, height / 2);
+            map[(x, y)] = Tile::floor('.');
+            (x, y)
+        }
+    };
+    objects[PLAYER].set_pos(player_x, player_y);
+
+    populate_regions(objects, &map, rng, table);
+
+    // the stairs go on the farthest tile the player can walk to
+    let (stairs_x, stairs_y) = farthest_reachable(player_x, player_y, &map);
+    let mut stairs = Object::new(stairs_x, stairs_y, '>', "stairs".into(), colors::WHITE, false);
+    stairs.always_visible = true;
+    objects.push(stairs);
+
+    map
+}
+
+fn make_cave_map(width: i32, height: i32, objects: &mut Vec<Object>, rng: &mut GameRng, table: &spawn::SpawnTable) -> Map {
+    // random fill, leaving a solid border so nothing can walk off the map
+    let mut walls = vec![vec![true; height as usize]; width as usize];
+    for x in 1..(width - 1) {
+        for y in 1..(height - 1) {
+            walls[x as usize][y as usize] = rng.gen::<f32>() < CAVE_FILL_PROBABILITY;
+        }
+    }
+
+    // smoothing passes: a cell becomes wall when most of its neighbourhood is wall
+    for _ in 0..CAVE_SMOOTHING_PASSES {
+        let previous = walls.clone();
+        for x in 1..(width - 1) {
+            for y in 1..(height - 1) {
+                let mut count = 0;
+                for dx in -1..2 {
+                    for dy in -1..2 {
+                        if previous[(x + dx) as usize][(y + dy) as usize] {
+                            count += 1;
+                        }
+                    }
+                }
+                walls[x as usize][y as usize] = count >= CAVE_WALL_THRESHOLD;
+            }
+        }
+    }
+
+    let mut map = Map::new(width, height, Tile::wall());
+    for (x, y) in map.positions().collect::<Vec<_>>() {
+        if !walls[x as usize][y as usize] {
+            map[(x, y)] = Tile::floor('.');
+        }
+    }
+
+    // only the largest cave is kept, every isolated pocket is filled back in
+    let mut regions = connected_regions(&map);
+    regions.sort_by_key(|region| region.len());
+    let largest = regions.pop().expect("O gerador de cavernas nao abriu nenhum espaco");
+    for region in regions {
+        for (x, y) in region {
+            map[(x, y)] = Tile::wall();
+        }
+    }
+
+    let (player_x, player_y) = largest[rng.gen_range(0, largest.len())];
+    objects[PLAYER].set_pos(player_x, player_y);
+
+    populate_regions(objects, &map, rng, table);
+
+    let (stairs_x, stairs_y) = farthest_reachable(player_x, player_y, &map);
+    let mut stairs = Object::new(stairs_x, stairs_y, '>', "stairs".into(), colors::WHITE, false);
+    stairs.always_visible = true;
+    objects.push(stairs);
+
+    map
+}
+
+/// carves a perfect maze with a recursive backtracker, then opens some of its dead ends
+/// into loops and cuts chambers into it. Returns the map and the number of chambers
+fn make_maze_map(width: i32, height: i32, objects: &mut Vec<Object>, rng: &mut GameRng, table: &spawn::SpawnTable, braid: f32) -> (Map, i32) {
+    let mut map = Map::new(width, height, Tile::wall());
+    // cells sit on the odd tiles, the even rows and columns between them are the walls
+    let (cols, rows) = ((width - 1) / 2, (height - 1) / 2);
+    let cell = |cx: i32, cy: i32| (cx * 2 + 1, cy * 2 + 1);
+    let in_maze = |cx: i32, cy: i32| cx >= 0 && cy >= 0 && cx < cols && cy < rows;
+
+    let mut visited = vec![vec![false; rows as usize]; cols as usize];
+    let start = (rng.gen_range(0, cols), rng.gen_range(0, rows));
+    visited[start.0 as usize][start.1 as usize] = true;
+    map[cell(start.0, start.1)] = Tile::floor('.');
+    let mut stack = vec![start];
+    while let Some(&(cx, cy)) = stack.last() {
+        let unvisited: Vec<(i32, i32)> = MAZE_DIRECTIONS.iter()
+            .map(|&(dx, dy)| (cx + dx, cy + dy))
+            .filter(|&(nx, ny)| in_maze(nx, ny) && !visited[nx as usize][ny as usize])
+            .collect();
+        if unvisited.is_empty() {
+            stack.pop();
+            continue;
+        }
+        let (nx, ny) = unvisited[rng.gen_range(0, unvisited.len())];
+        let (x, y) = cell(cx, cy);
+        map[(x + nx - cx, y + ny - cy)] = Tile::floor('.');
+        map[cell(nx, ny)] = Tile::floor('.');
+        visited[nx as usize][ny as usize] = true;
+        stack.push((nx, ny));
+    }
+
+    // braiding: knock down a wall of some dead ends, turning them into loops
+    for cx in 0..cols {
+        for cy in 0..rows {
+            let (x, y) = cell(cx, cy);
+            let open = MAZE_DIRECTIONS.iter().filter(|&&(dx, dy)| !map[(x + dx, y + dy)].blocked).count();
+            if open != 1 || rng.gen::<f32>() >= braid {
+                continue;
+            }
+            let walls: Vec<(i32, i32)> = MAZE_DIRECTIONS.iter()
+                .cloned()
+                .filter(|&(dx, dy)| in_maze(cx + dx, cy + dy) && map[(x + dx, y + dy)].blocked)
+                .collect();
+            if !walls.is_empty() {
+                let (dx, dy) = walls[rng.gen_range(0, walls.len())];
+                map[(x + dx, y + dy)] = Tile::floor('.');
+            }
+        }
+    }
+
+    let (player_x, player_y) = cell(start.0, start.1);
+    objects[PLAYER].set_pos(player_x, player_y);
+
+    // chambers are cut along the cell grid, so they always open onto the maze around them
+    let chambers = cmp::max(1, cols * rows / MAZE_CHAMBER_DENSITY);
+    for _ in 0..chambers {
+        let cells_w = cmp::min(cols, rng.gen_range(MAZE_CHAMBER_MIN_CELLS, MAZE_CHAMBER_MAX_CELLS + 1));
+        let cells_h = cmp::min(rows, rng.gen_range(MAZE_CHAMBER_MIN_CELLS, MAZE_CHAMBER_MAX_CELLS + 1));
+        let cx = rng.gen_range(0, cols - cells_w + 1);
+        let cy = rng.gen_range(0, rows - cells_h + 1);
+        let chamber = Rect::new(cx * 2, cy * 2, cells_w * 2, cells_h * 2);
+        for x in (chamber.x1 + 1)..chamber.x2 {
+            for y in (chamber.y1 + 1)..chamber.y2 {
+                map[(x, y)] = Tile::floor('.');
+            }
+        }
+        place_objects(chamber, objects, &map, rng, table);
+    }
+
+    let (stairs_x, stairs_y) = farthest_reachable(player_x, player_y, &map);
+    let mut stairs = Object::new(stairs_x, stairs_y, '>', "stairs".into(), colors::WHITE, false);
+    stairs.always_visible = true;
+    objects.push(stairs);
+
+    (map, chambers)
+}
+
+/// maps without rooms are split in square regions, and the ones open enough get populated
+fn populate_regions(objects: &mut Vec<Object>, map: &Map, rng: &mut GameRng, table: &spawn::SpawnTable) {
+    let mut x = 0;
+    while x + NOISE_REGION_SIZE < map.width() {
+        let mut y = 0;
+        while y + NOISE_REGION_SIZE < map.height() {
+            let region = Rect::new(x, y, NOISE_REGION_SIZE, NOISE_REGION_SIZE);
+            let open = ((region.x1 + 1)..region.x2)
+                .flat_map(|x| ((region.y1 + 1)..region.y2).map(move |y| (x, y)))
+                .filter(|&(x, y)| !map[(x, y)].blocked)
+                .count() as i32;
+            if open * 2 >= (NOISE_REGION_SIZE - 1).pow(2) {
+                place_objects(region, objects, map, rng, table);
+            }
+            y += NOISE_REGION_SIZE;
+        }
+        x += NOISE_REGION_SIZE;
+    }
+}
+
+/// groups every walkable tile into its 4-connected region
+fn connected_regions(map: &Map) -> Vec<Vec<(i32, i32)>> {
+    let mut visited = vec![vec![false; map.height() as usize]; map.width() as usize];
+    let mut regions = vec![];
+    for (x, y) in map.positions() {
+        if visited[x as usize][y as usize] || !map[(x, y)].passable() {
+            continue;
+        }
+        let mut region = vec![];
+        let mut queue = VecDeque::new();
+        visited[x as usize][y as usize] = true;
+        queue.push_back((x, y));
+        while let Some((cx, cy)) = queue.pop_front() {
+            region.push((cx, cy));
+            for (nx, ny) in map.neighbors(cx, cy) {
+                if !visited[nx as usize][ny as usize] && map[(nx, ny)].passable() {
+                    visited[nx as usize][ny as usize] = true;
+                    queue.push_back((nx, ny));
+                }
+            }
+        }
+        regions.push(region);
+    }
+    regions
+}
+
+/// breadth-first search over walkable tiles, returning the last tile reached
+fn farthest_reachable(start_x: i32, start_y: i32, map: &Map) -> (i32, i32) {
+    let distances = walk_distances(start_x, start_y, map);
+    let mut farthest = (start_x, start_y);
+    for (x, y) in map.positions() {
+        if distances[x as usize][y as usize] > distances[farthest.0 as usize][farthest.1 as usize] {
+            farthest = (x, y);
+        }
+    }
+    farthest
+}
+
+/// number of steps from the start to every walkable tile, `None` where it can't be reached
+fn walk_distances(start_x: i32, start_y: i32, map: &Map) -> Vec<Vec<Option<i32>>> {
+    let mut distances = vec![vec![None; map.height() as usize]; map.width() as usize];
+    let mut queue = VecDeque::new();
+
+    distances[start_x as usize][start_y as usize] = Some(0);
+    queue.push_back((start_x, start_y, 0));
+    while let Some((x, y, distance)) = queue.pop_front() {
+        for (nx, ny) in map.neighbors(x, y) {
+            if distances[nx as usize][ny as usize].is_none() && map[(nx, ny)].passable() {
+                distances[nx as usize][ny as usize] = Some(distance + 1);
+                queue.push_back((nx, ny, distance + 1));
+            }
+        }
+    }
+    distances
+}
+
+/// how many separate areas of passable tiles the map has, going in the four directions
+fn count_regions(map: &Map) -> i32 {
+    let height = map.height();
+    let index = |x: i32, y: i32| (x * height + y) as usize;
+    let mut seen = vec![false; (map.width() * height) as usize];
+    let mut regions = 0;
+    for (x, y) in map.positions() {
+        if seen[index(x, y)] || !map[(x, y)].passable() {
+            continue;
+        }
+        regions += 1;
+        seen[index(x, y)] = true;
+        let mut stack = vec![(x, y)];
+        while let Some((x, y)) = stack.pop() {
+            for (nx, ny) in map.neighbors(x, y) {
+                if !seen[index(nx, ny)] && map[(nx, ny)].passable() {
+                    seen[index(nx, ny)] = true;
+                    stack.push((nx, ny));
+                }
+            }
+        }
+    }
+    regions
+}
+
+/// makes sure every walkable tile, and the stairs in particular, can be reached from
+/// the player's start. A single search spreads from the player's region, crossing open
+/// tiles for free and rock a step at a time, and every pocket it runs into gets a tunnel
+/// along the way it took there. Fails if the stairs are still out of reach after that
+fn ensure_connectivity(map: &mut Map, objects: &[Object], corridor_width: i32) -> Result<GenerationStats, String> {
+    let (player_x, player_y) = objects[PLAYER].pos();
+    let stairs = objects.iter().find(|object| object.name == "stairs").map(|object| object.pos());
+
+    // the player and the stairs may sit on a leftover pillar
+    map[(player_x, player_y)] = Tile::floor('.');
+    if let Some((stairs_x, stairs_y)) = stairs {
+        if map[(stairs_x, stairs_y)].blocked {
+            map[(stairs_x, stairs_y)] = Tile::floor('.');
+        }
+    }
+
+    let regions_found = count_regions(map);
+    let (width, height) = (map.width(), map.height());
+    let index = |x: i32, y: i32| (x * height + y) as usize;
+    let mut cost = vec![i32::MAX; (width * height) as usize];
+    let mut came_from: Vec<Option<(i32, i32)>> = vec![None; (width * height) as usize];
+    let mut done = vec![false; (width * height) as usize];
+    let mut tunnels = vec![];
+
+    // open tiles go to the front of the queue and rock to the back, so it is popped in order of cost
+    let mut queue = VecDeque::new();
+    cost[index(player_x, player_y)] = 0;
+    queue.push_back((player_x, player_y));
+    while let Some((x, y)) = queue.pop_front() {
+        if done[index(x, y)] {
+            continue;
+        }
+        done[index(x, y)] = true;
+        let open = map[(x, y)].passable();
+        let dug_in = came_from[index(x, y)].is_some_and(|(px, py)| !map[(px, py)].passable());
+        if open && dug_in {
+            let mut tunnel = vec![];
+            let mut current = came_from[index(x, y)];
+            while let Some((cx, cy)) = current.filter(|&(cx, cy)| !map[(cx, cy)].passable()) {
+                tunnel.push((cx, cy));
+                current = came_from[index(cx, cy)];
+            }
+            tunnels.push(tunnel);
+        }
+
+        for (nx, ny) in map.neighbors(x, y) {
+            let neighbor = map[(nx, ny)];
+            // the border stays solid rock and vaults keep their walls
+            let border = nx == 0 || ny == 0 || nx == width - 1 || ny == height - 1;
+            if border || done[index(nx, ny)] || (neighbor.vault && !neighbor.passable()) {
+                continue;
+            }
+            let step = if neighbor.passable() { 0 } else { 1 };
+            let next = cost[index(x, y)] + step;
+            // on a tie an open tile is reached through its open neighbor, not dug into
+            if next < cost[index(nx, ny)] || (next == cost[index(nx, ny)] && open && step == 0) {
+                cost[index(nx, ny)] = next;
+                came_from[index(nx, ny)] = Some((x, y));
+                if step == 0 {
+                    queue.push_front((nx, ny));
+                } else {
+                    queue.push_back((nx, ny));
+                }
+            }
+        }
+    }
+
+    // carved only now, so the search saw the level as it was
+    for tunnel in &tunnels {
+        for &(x, y) in tunnel {
+            carve_corridor(x, y, corridor_width, map);
+        }
+    }
+    let tunnels_added = tunnels.len() as i32;
+
+    let distances = walk_distances(player_x, player_y, map);
+    let stairs_distance = match stairs {
+        Some((stairs_x, stairs_y)) => match distances[stairs_x as usize][stairs_y as usize] {
+            Some(distance) => Some(distance),
+            None => return Err(format!("as escadas em ({}, {}) nao podem ser alcancadas", stairs_x, stairs_y)),
+        },
+        None => None,
+    };
+
+    let mut floor_tiles = 0;
+    let mut water_tiles = 0;
+    for tile in map.iter() {
+        if tile.terrain.is_water() {
+            water_tiles += 1;
+        } else if !tile.blocked {
+            floor_tiles += 1;
+        }
+    }
+
+    Ok(GenerationStats {
+        rooms: 0,
+        floor_tiles: floor_tiles,
+        water_tiles: water_tiles,
+        regions_found: regions_found,
+        tunnels_added: tunnels_added,
+        stairs_distance: stairs_distance,
+        monsters: objects.iter().filter(|object| object.ai.is_some()).count() as i32,
+        items: objects.iter().filter(|object| object.item.is_some()).count() as i32,
+        attempts: 1,
+    })
+}
+
+fn make_map(config: &GenerationConfig, objects: &mut Vec<Object>, rng: &mut GameRng, contents: &RoomContents) -> (Map, i32) {
+
+    let mut map = Map::new(config.width, config.height, Tile::wall());
+    let mut tunneler = Tunneler::new(config.corridors);
+
+    let rooms = match config.layout {
+        enums::RoomLayout::Overlapping => make_overlapping_rooms(objects, &mut map, rng, contents, &mut tunneler),
+        enums::RoomLayout::Bsp => {
+            let mut rooms = vec![];
+            // keep the rooms off the last row and column, the border stays solid rock
+            let area = Rect::new(0, 0, config.width - 2, config.height - 2);
+            split_bsp(area, objects, &mut map, &mut rooms, rng, contents, &mut tunneler);
+            rooms
+        }
+    };
+
+    // the first room is where the player starts at, a vault's middle may well be a wall
+    let (first_room_x, first_room_y) = rooms[0].hook();
+    objects[PLAYER].set_pos(first_room_x, first_room_y);
+
+    for room in rooms.clone() {
+        if !room.vault && rng.gen::<bool>() {
+            create_water(room, &mut map, rng);
+        }
+    }
+
+    for room in rooms.iter().filter(|room| !room.vault && room.shape.has_straight_walls()) {
+        create_doors(room, &mut map, rng);
+    }
+
+    create_rivers_and_lakes(&mut map, &rooms, objects, rng);
+
+    let (last_room_x, last_room_y) = rooms[rooms.len() - 1].hook();
+    let mut stairs = Object::new(last_room_x, last_room_y, '>', "stairs".into(), colors::WHITE, false);
+    stairs.always_visible = true;
+    objects.push(stairs);
+
+
+    (map, rooms.len() as i32)
+}
+
+fn make_overlapping_rooms(objects: &mut Vec<Object>, map: &mut Map, rng: &mut GameRng, contents: &RoomContents, tunneler: &mut Tunneler) -> Vec<Rect> {
+
+    let mut rooms : Vec<Rect> = Vec::new();
+
+    // the number of rooms grows with the size of the map
+    let max_rooms = (map.width() + map.height()) / 10;
+    for _ in 0..max_rooms {
+        
+
+        // random width and height
+        let w = rng.gen_range(ROOM_MIN_SIZE, ROOM_MAX_SIZE + 1);
+        let h = rng.gen_range(ROOM_MIN_SIZE, ROOM_MAX_SIZE + 1);
+
+        // random position without going out of the boundaries of the map
+        let x = rng.gen_range(0, map.width() - w);
+        let y = rng.gen_range(0, map.height() - h);
+
+        let mut new_room = Rect::new(x, y, w, h);
+        
+        // rooms are allowed to overlap in this layout, the BSP one keeps them apart
+        let failed = false; //rooms.iter().any(|other_room| new_room.intersects_with(other_room));
+
+        if !failed {
+            // "paint" it to the map's tiles, sometimes from a prefab that fits in the map
+            let vault = random_prefab(contents.prefabs, rng)
+                .filter(|prefab| prefab.width() < map.width() - 3 && prefab.height() < map.height() - 3)
+                .and_then(|prefab| place_prefab(&prefab, map, &rooms, rng).map(|spot| (prefab, spot)));
+            if let Some((prefab, (x, y))) = vault {
+                new_room = create_prefab(&prefab, x, y, objects, map);
+            } else {
+                create_room(&mut new_room, objects, map, rng, contents.table);
+                // a room whose middle fell inside a vault can't be hooked up,
+                // its floor is left for the connectivity pass to reach
+                if map[new_room.hook()].vault {
+                    continue;
+                }
+            }
+
+            if let Some(prev_room) = rooms.last_mut() {
+                connect_rooms(prev_room, &mut new_room, map, rng, tunneler);
+            }
+
+            // finally, append the new room to the list
+            rooms.push(new_room);
+            //rand::thread_rng().shuffle(&mut rooms);
+        }
+    }
+
+    rooms
+}
+
+/// splits the area in two until the pieces are small enough to hold a single room,
+/// then joins every pair of sibling partitions with a tunnel.
+/// Returns the indices in `rooms` of the rooms created inside this area
+fn split_bsp(area: Rect, objects: &mut Vec<Object>, map: &mut Map, rooms: &mut Vec<Rect>, rng: &mut GameRng, contents: &RoomContents, tunneler: &mut Tunneler) -> Vec<usize> {
+    let w = area.x2 - area.x1;
+    let h = area.y2 - area.y1;
+
+    let split_vertically = if w > BSP_MAX_LEAF_SIZE && h > BSP_MAX_LEAF_SIZE {
+        Some(rng.gen::<bool>())
+    } else if w > BSP_MAX_LEAF_SIZE {
+        Some(true)
+    } else if h > BSP_MAX_LEAF_SIZE {
+        Some(false)
+    } else {
+        None
+    };
+
+    let (first, second) = match split_vertically {
+        Some(true) => {
+            let split = rng.gen_range(BSP_MIN_LEAF_SIZE, w - BSP_MIN_LEAF_SIZE + 1);
+            (Rect::new(area.x1, area.y1, split, h), Rect::new(area.x1 + split, area.y1, w - split, h))
+        }
+        Some(false) => {
+            let split = rng.gen_range(BSP_MIN_LEAF_SIZE, h - BSP_MIN_LEAF_SIZE + 1);
+            (Rect::new(area.x1, area.y1, w, split), Rect::new(area.x1, area.y1 + split, w, h - split))
+        }
+        None => {
+            // leaf: a room strictly inside the area, so it never touches a neighbour's room
+            let vault = random_prefab(contents.prefabs, rng)
+                .filter(|prefab| prefab.width() < w - 2 && prefab.height() < h - 2);
+            if let Some(prefab) = vault {
+                // a tile away from the map's border too, so there is room for a tunnel outside its door
+                let x = rng.gen_range(cmp::max(area.x1 + 1, 2), area.x2 - prefab.width() + 1);
+                let y = rng.gen_range(cmp::max(area.y1 + 1, 2), area.y2 - prefab.height() + 1);
+                let room = create_prefab(&prefab, x, y, objects, map);
+                rooms.push(room);
+                return vec![rooms.len() - 1];
+            }
+
+            let room_w = rng.gen_range(ROOM_MIN_SIZE, cmp::min(ROOM_MAX_SIZE, w - 2) + 1);
+            let room_h = rng.gen_range(ROOM_MIN_SIZE, cmp::min(ROOM_MAX_SIZE, h - 2) + 1);
+            let x = rng.gen_range(area.x1 + 1, area.x2 - room_w);
+            let y = rng.gen_range(area.y1 + 1, area.y2 - room_h);
+            let mut room = Rect::new(x, y, room_w, room_h);
+            debug_assert!(!rooms.iter().any(|other_room| room.intersects_with(other_room)));
+
+            create_room(&mut room, objects, map, rng, contents.table);
+            rooms.push(room);
+            return vec![rooms.len() - 1];
+        }
+    };
+
+    let first_ids = split_bsp(first, objects, map, rooms, rng, contents, tunneler);
+    let second_ids = split_bsp(second, objects, map, rooms, rng, contents, tunneler);
+
+    // join the closest pair of rooms across the split, preferring rooms with few tunnels
+    let mut best = None;
+    for &a in &first_ids {
+        for &b in &second_ids {
+            let (ax, ay) = rooms[a].hook();
+            let (bx, by) = rooms[b].hook();
+            let distance = (((ax - bx).pow(2) + (ay - by).pow(2)) as f32).sqrt();
+            let score = distance + ((rooms[a].connections + rooms[b].connections) * BSP_CONNECTION_PENALTY) as f32;
+            if best.is_none_or(|(_, _, best_score)| score < best_score) {
+                best = Some((a, b, score));
+            }
+        }
+    }
+    if let Some((a, b, _)) = best {
+        let (first_rooms, second_rooms) = rooms.split_at_mut(b);
+        connect_rooms(&mut first_rooms[a], &mut second_rooms[0], map, rng, tunneler);
+    }
+
+    let mut ids = first_ids;
+    ids.extend(second_ids);
+    ids
+}
+
+fn connect_rooms(prev_room: &mut Rect, new_room: &mut Rect, map: &mut Map, rng: &mut GameRng, tunneler: &mut Tunneler) {
+    let (prev_x, prev_y) = prev_room.hook();
+    let (new_x, new_y) = new_room.hook();
+    let width = tunneler.corridors.width;
+
+    let chance = rng.gen::<f32>();
+    if tunneler.corridors.style == enums::CorridorStyle::Winding {
+        tunneler.create_winding_tunnel(prev_x, prev_y, new_x, new_y, map, rng);
+    } else if chance <= 0.1 {
+        create_d_tunnel(prev_y, new_y, prev_x, new_x, width, map);
+    } else if chance <= 0.50 {
+        create_h_tunnel(prev_x, new_x, prev_y, width, map);
+        create_v_tunnel(prev_y, new_y, new_x, width, map);
+    }
+    else {
+        create_v_tunnel(prev_y, new_y, prev_x, width, map);
+        create_h_tunnel(prev_x, new_x, new_y, width, map);
+    }
+
+    prev_room.connections += 1;
+    new_room.connections += 1;
+}
+
+/// generates the level at `depth`, with the player at `objects[PLAYER]` moved to its start.
+/// A level the connectivity pass can't fix is generated again from a seed derived from its
+/// own, and only when none of them works out the error comes back, with `objects` as they were
+pub fn make_level(depth: i32, dungeon: &Dungeon, objects: &mut Vec<Object>) -> Result<(Map, GenerationStats), String> {
+    let arriving = objects.clone();
+    let mut error = String::new();
+    for attempt in 0..LEVEL_ATTEMPTS {
+        // every level has its own generator seeded from the run's seed, so a level
+        // looks the same no matter what happened on the floors above it
+        let mut seed = vec![dungeon.seed as usize, depth as usize];
+        if attempt > 0 {
+            seed.push(attempt);
+        }
+        let mut rng = GameRng::from_seed(&seed[..]);
+        match generate_level(depth, dungeon, objects, &mut rng) {
+            Ok((map, mut stats)) => {
+                stats.attempts = attempt as i32 + 1;
+                return Ok((map, stats));
+            }
+            Err(failure) => {
+                debug!("Nivel {} descartado na tentativa {}: {}", depth, attempt + 1, failure);
+                *objects = arriving.clone();
+                error = failure;
+            }
+        }
+    }
+    Err(format!("nivel {} sem conserto depois de {} tentativas: {}", depth, LEVEL_ATTEMPTS, error))
+}
+
+/// a single try at the level, with its own random generator
+fn generate_level(depth: i32, dungeon: &Dungeon, objects: &mut Vec<Object>, rng: &mut GameRng) -> Result<(Map, GenerationStats), String> {
+    let table = dungeon.spawn_tables.for_level(depth);
+
+    let config = &dungeon.config;
+    let (mut map, rooms) = match config.generator {
+        enums::Generator::Rooms => make_map(config, objects, rng, &RoomContents { table: &table, prefabs: &dungeon.prefabs }),
+        enums::Generator::Perlin => (make_perlin_map(config.width, config.height, objects, rng, &table), 0),
+        enums::Generator::Caves => (make_cave_map(config.width, config.height, objects, rng, &table), 0),
+        enums::Generator::Maze => make_maze_map(config.width, config.height, objects, rng, &table, config.braid),
+    };
+
+    if depth > 1 {
+        // the way back up is where the player arrives
+        let (x, y) = objects[PLAYER].pos();
+        let mut stairs = Object::new(x, y, '<', "stairs up".into(), colors::WHITE, false);
+        stairs.always_visible = true;
+        objects.push(stairs);
+    }
+
+    let mut stats = ensure_connectivity(&mut map, objects, config.corridors.width)?;
+    stats.rooms = rooms;
+    let theme = dungeon.themes.for_level(depth, dungeon.config.generator.name());
+    apply_theme(&mut map, theme, rng);
+    Ok((map, stats))
+}
+
+#[cfg(feature = "tcod")]
+fn print_level_stats(depth: i32, dungeon: &Dungeon, stats: &GenerationStats) {
+    let theme = dungeon.themes.for_level(depth, dungeon.config.generator.name());
+    debug!("Nivel {} ({}): {:?}", depth, theme.name, stats);
+}
+
+/// recolors every tile by its terrain and scatters the theme's glyphs over the plain floor
+fn apply_theme(map: &mut Map, theme: &theme::Theme, rng: &mut GameRng) {
+    let glyphs: Vec<char> = theme.floor_glyphs.chars().collect();
+    for tile in map.iter_mut() {
+        // doors keep their wood colors whatever the level looks like
+        if tile.door.is_some() {
+            continue;
+        }
+        let palette = match tile.terrain {
+            enums::Terrain::Wall => theme.wall,
+            enums::Terrain::Floor => theme.ground,
+            enums::Terrain::ShallowWater => theme.water,
+            enums::Terrain::DeepWater => theme.deep_water,
+            // and so do bridges
+            enums::Terrain::Bridge => theme::Palette { light: COLOR_LIGHT_BRIDGE, dark: COLOR_DARK_BRIDGE },
+        };
+        tile.light_color = palette.light;
+        tile.dark_color = palette.dark;
+        if tile.terrain == enums::Terrain::Floor && tile.char == '.' && !glyphs.is_empty() {
+            tile.char = glyphs[rng.gen_range(0, glyphs.len())];
+        }
+    }
+}
+
+/// stores the current floor and moves the player to `depth`, generating it on the first visit
+#[cfg(feature = "tcod")]
+fn change_level(depth: i32, dungeon: &mut Dungeon, map: &mut Map, objects: &mut Vec<Object>, tcod: &mut Tcod) -> bool {
+    let going_down = depth > dungeon.depth;
+    let left_depth = dungeon.depth;
+
+    let left_objects = objects.split_off(PLAYER + 1);
+    let left_map = mem::replace(map, Map::new(0, 0, Tile::wall()));
+    dungeon.levels.insert(dungeon.depth, Level { map: left_map, objects: left_objects });
+    dungeon.depth = depth;
+
+    match dungeon.levels.remove(&depth) {
+        Some(level) => {
+            *map = level.map;
+            objects.extend(level.objects);
+            // arrive on the stairs that lead back where the player came from
+            let arrival = if going_down { "stairs up" } else { "stairs" };
+            if let Some((x, y)) = objects.iter().find(|object| object.name == arrival).map(|object| object.pos()) {
+                objects[PLAYER].set_pos(x, y);
+            }
+        }
+        None => match make_level(depth, dungeon, objects) {
+            Ok((new_map, stats)) => {
+                print_level_stats(depth, dungeon, &stats);
+                *map = new_map;
+            }
+            Err(error) => {
+                // the player stays on the level they were leaving
+                eprintln!("{}", error);
+                let level = dungeon.levels.remove(&left_depth).unwrap();
+                dungeon.depth = left_depth;
+                *map = level.map;
+                objects.extend(level.objects);
+                return false;
+            }
+        },
+    }
+
+    initialise_fov(map, tcod);
+    true
+}
+
+/// rebuilds the FOV map for this map, from which tiles block sight and movement
+#[cfg(feature = "tcod")]
+fn initialise_fov(map: &Map, tcod: &mut Tcod) {
+    tcod.fov = FovMap::new(map.width(), map.height());
+    for (x, y) in map.positions() {
+        tcod.fov.set(x, y,
+                    !map[(x, y)].block_sight,
+                    !map[(x, y)].blocked);
+    }
+}
+
+#[cfg(feature = "tcod")]
+fn standing_on(objects: &[Object], name: &str) -> bool {
+    objects.iter().any(|object| object.name == name && object.pos() == objects[PLAYER].pos())
+}
+
+#[cfg(feature = "tcod")]
+fn render_all(tcod: &mut Tcod, objects: &[Object], map: &mut Map,
+              fov_recompute: bool,messages: &Messages, dungeon: &Dungeon) {
+
+    if fov_recompute {
+        // recompute FOV if needed (the player moved or something)
+        let player = &objects[PLAYER];
+        tcod.fov.compute_fov(player.x, player.y, objects[PLAYER].torch_radius, FOV_LIGHT_WALLS, FOV_ALGO);
+        let vec = tcod.camera.offset();
+        for y in (vec.1)..(vec.1 + tcod.camera.height()) {
+            for x in (vec.0)..(vec.0 + tcod.camera.width()) {
+                if map.in_bounds(x, y) && tcod.fov.is_in_fov(x, y) {
+                    // since it's visible, explore it
+                    map[(x, y)].explored = true;
+                }
+            }
+        }
+        render_map(tcod, objects, map);
+    }
+
+    tcod.renderer.fill(0, PANEL_Y, SCREEN_WIDTH, PANEL_HEIGHT, colors::BLACK, 1.0);
+
+    let mut y = MSG_HEIGHT as i32;
+    for msg in messages.iter().rev() {
+        let msg_height = renderer::text_height(&msg.text, MSG_WIDTH);
+        y -= msg_height;
+        if y < 0 {
+            break;
+        }
+        tcod.renderer.print_rect(MSG_X, PANEL_Y + y, MSG_WIDTH, &msg.text, msg.color);
+    }
+
+    // show the player's stats
+    let hp = objects[PLAYER].fighter.map_or(0, |f| f.hp);
+    let max_hp = objects[PLAYER].fighter.map_or(0, |f| f.max_hp);
+    let mana = objects[PLAYER].fighter.map_or(0, |f| f.mana);
+    let max_mana = objects[PLAYER].fighter.map_or(0, |f| f.max_mana);
+    render_bar(&mut *tcod.renderer, 1, PANEL_Y + 1, BAR_WIDTH, "HP", hp, max_hp, colors::LIGHT_RED, colors::DARKER_RED);
+    render_bar(&mut *tcod.renderer, 1, PANEL_Y + 3, BAR_WIDTH, "MP", mana, max_mana, colors::LIGHT_BLUE, colors::DARKER_BLUE);
+
+    tcod.renderer.print(1, PANEL_Y + 5, &format!("Nivel: {}", dungeon.depth), colors::WHITE, TextAlignment::Left);
+
+    // show the seed so the run can be reproduced
+    tcod.renderer.print(1, PANEL_Y + 6, &format!("Seed: {}", dungeon.seed), colors::DARK_GREY, TextAlignment::Left);
+
+    let names = get_names_under_mouse(tcod.mouse, objects, map, &mut tcod.fov, &tcod.camera);
+    tcod.renderer.print(MSG_WIDTH, PANEL_Y, &names, colors::LIGHT_GREY, TextAlignment::Left);
+    
+}
+
+#[cfg(feature = "tcod")]
+fn move_away(id: usize, target_x: i32, target_y: i32, map: &Map, objects: &mut [Object], messages: &mut Messages, rng: &mut GameRng) {
+    // vector from this object to the target, and distance
+    let dx = target_x - objects[id].x;
+    let dy = target_y - objects[id].y;
+    let distance = -((dx.pow(2) + dy.pow(2)) as f32).sqrt();
+
+    // normalize it to length 1 (preserving direction), then round it and
+    // convert to integer so the movement is restricted to the map grid
+    let dx = (dx as f32 / distance).round() as i32;
+    let dy = (dy as f32 / distance).round() as i32;
+    move_by(id, dx, dy, map, objects, messages, rng);
+}
+
+#[cfg(feature = "tcod")]
+fn move_towards(id: usize, target_x: i32, target_y: i32, map: &Map, objects: &mut [Object], messages: &mut Messages, rng: &mut GameRng) {
+    let (x, y) = objects[id].pos();
+    let distance_from = |x: i32, y: i32| (((target_x - x).pow(2) + (target_y - y).pow(2)) as f32).sqrt();
+    let distance = distance_from(x, y);
+    let swimming = map[(x, y)].terrain == enums::Terrain::DeepWater;
+
+    // of the steps that get closer to the target, take the one that is cheapest
+    // to walk, counting each extra turn of slow terrain as one more tile away
+    let mut best: Option<(i32, i32, f32)> = None;
+    for dx in -1..2 {
+        for dy in -1..2 {
+            let (nx, ny) = (x + dx, y + dy);
+            if (dx, dy) == (0, 0) || is_blocked(nx, ny, map, objects) {
+                continue;
+            }
+            let terrain = map[(nx, ny)].terrain;
+            // monsters can't swim, they only cross deep water to get out of it
+            if terrain == enums::Terrain::DeepWater && !swimming {
+                continue;
+            }
+            let new_distance = distance_from(nx, ny);
+            if new_distance >= distance {
+                continue;
+            }
+            let score = new_distance + (terrain.movement_cost() - 1) as f32;
+            if best.is_none_or(|(_, _, best_score)| score < best_score) {
+                best = Some((dx, dy, score));
+            }
+        }
+    }
+
+    if let Some((dx, dy, _)) = best {
+        move_by(id, dx, dy, map, objects, messages, rng);
+    }
+}
+
+#[cfg(feature = "tcod")]
+fn player_move_or_attack(dx: i32, dy: i32, map: &mut Map, objects: &mut Vec<Object>, messages: &mut Messages, inventory: &mut Vec<Object>, fov_map: &mut FovMap, rng: &mut GameRng) -> Option<Object> {
+    
+    // a confused player staggers in a random direction
+    let (dx, dy) = if objects[PLAYER].confused > 0 {
+        objects[PLAYER].confused -= 1;
+        if objects[PLAYER].confused == 0 {
+            message(messages, "Voce nao esta mais confuso.", colors::WHITE, enums::MessageCategory::Combat);
+        }
+        (rng.gen_range(-1, 2), rng.gen_range(-1, 2))
+    } else {
+        (dx, dy)
+    };
+
+    let x = objects[PLAYER].x;
+    let y = objects[PLAYER].y;
+
+    let x2 = x + dx;
+    let y2 = y + dy;
+
+    // bumping a closed door opens it
+    if map[(x2, y2)].door == Some(enums::DoorState::Closed) {
+        set_door(x2, y2, enums::DoorState::Open, map, fov_map);
+        message(messages, "Voce abre a porta.", colors::WHITE, enums::MessageCategory::System);
+        return None;
+    }
+
+    let item_id = objects.iter().position(|obj| {
+        obj.pos() == (x2, y2) && obj.item.is_some()
+    });
+    if let Some(item_id) = item_id {
+        pick_item_up(item_id, objects, inventory, messages);
+    }
+
+    // the coordinates the player is moving to/attacking
+    
+
+    // try to find an attackable object there
+    let target_id = objects.iter().position(|object| {
+        object.fighter.is_some() && object.pos() == (x2, y2)
+    });
+    // attack if target found, move otherwise
+    match target_id {
+        Some(target_id) => {
+            let footing = map[(x, y)].terrain;
+            let (player, target) = mut_two(PLAYER, target_id, objects);
+            player.attack(target, footing, messages);
+            return None;
+
+            // let new_player = player.merge(target);
+            // return Some(new_player);
+        }
+        None => {
+            move_by(PLAYER, dx, dy, map, objects, messages, rng);
+            return None;
+        }
+    }
+    
+}
+
+/// moves the player, falling to the next level if the step set off a pit
+#[cfg(feature = "tcod")]
+fn player_step(dx: i32, dy: i32, map: &mut Map, objects: &mut Vec<Object>, messages: &mut Messages, inventory: &mut Vec<Object>, tcod: &mut Tcod, rng: &mut GameRng, dungeon: &mut Dungeon) -> Option<Object> {
+    let ret = player_move_or_attack(dx, dy, map, objects, messages, inventory, &mut tcod.fov, rng);
+    let pos = objects[PLAYER].pos();
+    let fell = objects.iter().any(|object| {
+        object.pos() == pos && object.trap.is_some_and(|trap| trap.kind == enums::TrapKind::Pit)
+    });
+    if fell && objects[PLAYER].alive {
+        let depth = dungeon.depth + 1;
+        if change_level(depth, dungeon, map, objects, tcod) {
+            message(messages, format!("Voce cai ate o nivel {}.", depth), colors::VIOLET, enums::MessageCategory::System);
+        } else {
+            message(messages, "O caminho esta bloqueado.", colors::WHITE, enums::MessageCategory::System);
+        }
+    }
+    ret
+}
+
+/// takes the monsters that fell through a pit off the level, into the one below if it was already visited
+#[cfg(feature = "tcod")]
+fn drop_fallen_monsters(objects: &mut Vec<Object>, dungeon: &mut Dungeon) {
+    while let Some(id) = objects.iter().position(|object| object.ai == Some(enums::Ai::Fallen)) {
+        let mut monster = objects.remove(id);
+        monster.ai = Some(enums::Ai::Basic);
+        if let Some(level) = dungeon.levels.get_mut(&(dungeon.depth + 1)) {
+            // it lands where the player would arrive from above
+            if let Some(stairs) = level.objects.iter().find(|object| object.name == "stairs up") {
+                monster.set_pos(stairs.x, stairs.y);
+                level.objects.push(monster);
+            }
+        }
+    }
+}
+
+/// tries to disarm a known trap next to or under the player, returns whether there was one
+#[cfg(feature = "tcod")]
+fn disarm_trap(objects: &mut Vec<Object>, messages: &mut Messages, rng: &mut GameRng) -> bool {
+    let trap_id = objects.iter().position(|object| {
+        object.trap.is_some_and(|trap| !trap.hidden) &&
+            (object.x - objects[PLAYER].x).abs() <= 1 && (object.y - objects[PLAYER].y).abs() <= 1
+    });
+    let trap_id = match trap_id {
+        Some(trap_id) => trap_id,
+        None => {
+            message(messages, "Nao ha armadilha conhecida por perto.", colors::WHITE, enums::MessageCategory::System);
+            return false;
+        }
+    };
+
+    if rng.gen::<f32>() < DISARM_CHANCE {
+        let trap = objects.remove(trap_id);
+        message(messages, format!("Voce desarma o {}.", trap.name), colors::LIGHT_GREEN, enums::MessageCategory::System);
+    } else {
+        message(messages, "Voce nao consegue desarmar a armadilha.", colors::WHITE, enums::MessageCategory::System);
+    }
+    true
+}
+
+#[cfg(feature = "tcod")]
+fn handle_keys(key: Key, map: &mut Map, objects: &mut Vec<Object>, messages: &mut Messages, inventory: &mut Vec<Object>, tcod: &mut Tcod, rng: &mut GameRng, dungeon: &mut Dungeon) -> (enums::PlayerAction, Option<Object>) {
+    use renderer::KeyCode::*;
+    use enums::PlayerAction::*;
+
+    let player_alive = objects[PLAYER].alive;
+
+    match (key, player_alive) {
+        (Key { code: Enter, alt: true, .. }, _) => {
+            // Alt+Enter: toggle fullscreen
+            tcod.renderer.toggle_fullscreen();
+            (DidntTakeTurn, None)
+        }
+        (Key { code: Escape, .. }, _) => return (Exit, None),  // exit game
+        (Key {printable: 'g', ..}, true) => {
+            for tile in map.iter_mut() {
+                tile.explored = !tile.explored;
+            }
+            (DidntTakeTurn, None)
+        }, 
+        (Key {printable: 'i', ..}, true) => {
+            let index = inventory_menu(inventory, "Selecione o item que desejar.\n", &mut *tcod.renderer);
+            if let Some(index) = index {
+                use_item(index, inventory, objects, messages, map, tcod, rng);
+            }
+            (DidntTakeTurn, None)
+        },
+        (Key {printable: 'x', ..}, true) => {
+            let index = inventory_menu(inventory, "Selecione o item que deseja largar.\n", &mut *tcod.renderer);
+            if let Some(index) = index {
+                drop_item(index, inventory, objects, map, messages);
+                (TookTurn, None)
+            } else {
+                (DidntTakeTurn, None)
+            }
+        },
+        (Key {printable: '+', ..}, true) => {
+            objects[PLAYER].torch_radius += 1;
+            (DidntTakeTurn, None)
+        },
+        (Key {printable: '-', ..}, true) => {
+            objects[PLAYER].torch_radius -= 1;
+            (DidntTakeTurn, None)
+        },
+        (Key {printable: '>', ..}, true) => {
+            // go down the stairs, if the player is standing on them
+            if standing_on(objects, "stairs") {
+                let depth = dungeon.depth + 1;
+                if change_level(depth, dungeon, map, objects, tcod) {
+                    message(messages, format!("Voce desce para o nivel {}.", depth), colors::VIOLET, enums::MessageCategory::System);
+                } else {
+                    message(messages, "O caminho esta bloqueado.", colors::WHITE, enums::MessageCategory::System);
+                }
+            } else {
+                message(messages, "Nao ha escadas para descer aqui.", colors::WHITE, enums::MessageCategory::System);
+            }
+            (DidntTakeTurn, None)
+        },
+        (Key {printable: '<', ..}, true) => {
+            if standing_on(objects, "stairs up") {
+                let depth = dungeon.depth - 1;
+                if change_level(depth, dungeon, map, objects, tcod) {
+                    message(messages, format!("Voce sobe para o nivel {}.", depth), colors::VIOLET, enums::MessageCategory::System);
+                } else {
+                    message(messages, "O caminho esta bloqueado.", colors::WHITE, enums::MessageCategory::System);
+                }
+            } else {
+                message(messages, "Nao ha escadas para subir aqui.", colors::WHITE, enums::MessageCategory::System);
+            }
+            (DidntTakeTurn, None)
+        },
+        (Key {printable: 'p', ..}, _) => {
+            // dump the level to text, JSON and an image, to review the generators
+            let prefix = format!("mapa_{}_nivel_{}", dungeon.seed, dungeon.depth);
+            match export::export_map(map, objects, &prefix) {
+                Ok(()) => message(messages, format!("Mapa exportado para {}.txt/.json/.png", prefix), colors::LIGHT_GREY, enums::MessageCategory::System),
+                Err(error) => message(messages, format!("Nao foi possivel exportar o mapa: {}", error), colors::RED, enums::MessageCategory::System),
+            }
+            (DidntTakeTurn, None)
+        },
+        (Key {printable: 'f', ..}, true) => {
+            search(map, objects, &mut tcod.fov, messages, rng);
+            (TookTurn, None)
+        },
+        (Key {printable: 'v', ..}, true) => {
+            if disarm_trap(objects, messages, rng) {
+                (TookTurn, None)
+            } else {
+                (DidntTakeTurn, None)
+            }
+        },
+        (Key {printable: 'c', ..}, true) => {
+            if close_doors(map, objects, &mut tcod.fov, messages) {
+                (TookTurn, None)
+            } else {
+                (DidntTakeTurn, None)
+            }
+        },
+        (Key {printable: 'm', ..}, _) => {
+            tcod.minimap.toggle();
+            (DidntTakeTurn, None)
+        },
+        (Key {printable: 'L', ..}, _) => {
+            history::show_log(&mut *tcod.renderer, messages);
+            (DidntTakeTurn, None)
+        },
+        (Key {printable: 'M', ..}, _) => {
+            minimap::show_full(&mut *tcod.renderer, map, objects, &tcod.fov);
+            (DidntTakeTurn, None)
+        },
+        // h, j, k and l scroll the minimap while it is open
+        (Key {printable: 'h', ..}, _) if tcod.minimap.visible => {
+            tcod.minimap.scroll(-1, 0, map, objects);
+            (DidntTakeTurn, None)
+        },
+        (Key {printable: 'j', ..}, _) if tcod.minimap.visible => {
+            tcod.minimap.scroll(0, 1, map, objects);
+            (DidntTakeTurn, None)
+        },
+        (Key {printable: 'k', ..}, _) if tcod.minimap.visible => {
+            tcod.minimap.scroll(0, -1, map, objects);
+            (DidntTakeTurn, None)
+        },
+        (Key {printable: 'l', ..}, _) if tcod.minimap.visible => {
+            tcod.minimap.scroll(1, 0, map, objects);
+            (DidntTakeTurn, None)
+        },
+        // movement keys
+        (Key { code: Up, .. }, true) | (Key { printable: 'w', .. }, true) => {
+            let ret = player_step(0, -1, map, objects, messages, inventory, tcod, rng, dungeon);
+            (TookTurn, ret)
+        }
+        (Key { code: Down, .. }, true) | (Key { printable: 's', .. }, true) => {
+            let ret = player_step(0, 1, map, objects, messages, inventory, tcod, rng, dungeon);
+            (TookTurn, ret)
+        }
+        (Key { code: Left, .. }, true) | (Key { printable: 'a', .. }, true) => {
+            let ret = player_step(-1, 0, map, objects, messages, inventory, tcod, rng, dungeon);
+            (TookTurn, ret)
+        }
+        (Key { code: Right, .. }, true) | (Key { printable: 'd', .. }, true) => {
+            let ret = player_step(1, 0, map, objects, messages, inventory, tcod, rng, dungeon);
+            (TookTurn, ret)
+        }
+
+        _ => (DidntTakeTurn, None),
+    }
+}
+
+#[cfg(feature = "tcod")]
+fn get_names_under_mouse(mouse: Mouse, objects: &[Object], map: &Map, fov_map: &FovMap, camera: &Camera) -> String {
+    // the mouse can be over the panel or past the edge of the map
+    let (x, y) = match camera.to_map(mouse.cx, mouse.cy) {
+        Some(position) => position,
+        None => return String::new(),
+    };
+    let tile = match map.get(x, y) {
+        Some(tile) => tile.char,
+        None => return String::new(),
+    };
+    
+    // create a list with the names of all objects at the mouse's coordinates and in FOV
+    let mut names = objects
+        .iter()
+        .filter(|obj| {obj.pos() == (x, y) && fov_map.is_in_fov(obj.x, obj.y)})
+        .filter(|obj| obj.trap.is_none_or(|trap| !trap.hidden))
+        .map(|obj| obj.name.clone())
+        .collect::<Vec<_>>();
+
+    if names.len() < 1 {
+        names.push(tile.to_string());
+    }
+    
+    
+
+    names.join(", ")  // join the names, separated by commas
+}
+
+fn place_objects(room: Rect, objects: &mut Vec<Object>, map: &Map, rng: &mut GameRng, table: &spawn::SpawnTable) {
+    // choose random number of monsters
+    let num_monsters = rng.gen_range(0, table.max_monsters + 1);
+
+    for _ in 0..num_monsters {
+        // choose random spot for this 
+
+        let mut x;
+        let mut y;
+        while {
+            x = rng.gen_range(room.x1 + 1, room.x2);
+            y = rng.gen_range(room.y1 + 1, room.y2);
+            
+            is_blocked(x, y, map, objects)
+        } {}
+        let monster = match spawn::choose(&table.monsters, rng) {
+            Some(name) => spawn_monster(name, x, y).unwrap_or_else(|| panic!("Monstro desconhecido na tabela de spawn: {}", name)),
+            None => continue
+        };
+        objects.push(monster);
+    }
+
+    let num_items = rng.gen_range(0, table.max_items + 1);
+    for _ in 0..num_items {
+
+        let mut x;
+        let mut y;
+
+        while {
+            
+            x = rng.gen_range(room.x1 + 1, room.x2);
+            y = rng.gen_range(room.y1 + 1, room.y2);
+            
+            is_blocked(x, y, map, objects)
+        } {}
+        if map[(x, y)].terrain.swallows_items() {
+            // it would just sink
+            continue;
+        }
+
+        let item = match spawn::choose(&table.items, rng) {
+            Some(name) => spawn_item(name, x, y).unwrap_or_else(|| panic!("Item desconhecido na tabela de spawn: {}", name)),
+            None => continue
+        };
+                
+        objects.push(item);
+
+    }
+
+    let num_traps = rng.gen_range(0, table.max_traps + 1);
+    for _ in 0..num_traps {
+        let x = rng.gen_range(room.x1 + 1, room.x2);
+        let y = rng.gen_range(room.y1 + 1, room.y2);
+        // only on dry floor that is free, with a single trap per tile
+        let free = !is_blocked(x, y, map, objects) && map[(x, y)].terrain == enums::Terrain::Floor &&
+            !objects.iter().any(|object| object.pos() == (x, y));
+        if !free {
+            continue;
+        }
+        let trap = match spawn::choose(&table.traps, rng) {
+            Some(name) => spawn_trap(name, x, y).unwrap_or_else(|| panic!("Armadilha desconhecida na tabela de spawn: {}", name)),
+            None => continue
+        };
+        objects.push(trap);
+    }
+
+}
+
+/// creates the monster the spawn tables and prefabs know by this name
+fn spawn_monster(name: &str, x: i32, y: i32) -> Option<Object> {
+    let mut monster = match name {
+        "duergar" => Object::duergar(x, y),
+        "troll" => Object::troll(x, y),
+        "goblin" => Object::goblin(x, y),
+        _ => return None
+    };
+    monster.alive = true;
+    Some(monster)
+}
+
+/// creates the trap the spawn tables know by this name, hidden until found
+fn spawn_trap(name: &str, x: i32, y: i32) -> Option<Object> {
+    let kind = match name {
+        "dart" => enums::TrapKind::Dart,
+        "teleport" => enums::TrapKind::Teleport,
+        "alarm" => enums::TrapKind::Alarm,
+        "confusion_gas" => enums::TrapKind::ConfusionGas,
+        "pit" => enums::TrapKind::Pit,
+        _ => return None
+    };
+    Some(Object::trap(x, y, kind))
+}
+
+/// creates the item the spawn tables and prefabs know by this name
+fn spawn_item(name: &str, x: i32, y: i32) -> Option<Object> {
+    match name {
+        "heal_potion" => Some(Object::heal_potion(x, y)),
+        "fire_ball" => Some(Object::fire_ball(x, y)),
+        "confusion_spell" => Some(Object::confusion_spell(x, y)),
+        "fear_spell" => Some(Object::fear_spell(x, y)),
+        "merge_spell" => Some(Object::merge_spell(x, y)),
+        _ => None
+    }
+}
+
+fn is_blocked(x: i32, y: i32, map: &Map, objects: &[Object]) -> bool {
+    // first test the map tile
+
+    if map[(x, y)].blocked {
+        return true;
+    }
+    // now check for any blocking objects
+    let r = objects.iter().any(|object| {
+        object.blocks && object.pos() == (x, y)
+    });
+    return r;
+   
+}
+
+/// draws the part of the level the camera shows, with the objects in view and the minimap
+#[cfg(feature = "tcod")]
+fn render_map(tcod: &mut Tcod, objects: &[Object], map: &Map) {
+    let player = &objects[PLAYER];
+    let camera = tcod.camera;
+    let vec = camera.offset();
+    for y in (vec.1)..(vec.1 + camera.height()) {
+        for x in (vec.0)..(vec.0 + camera.width()) {
+            
+            // the screen can reach past the edge of a small map
+            let tile = match map.get(x, y) {
+                Some(&tile) => tile,
+                None => {
+                    tcod.renderer.fill(x - vec.0, y - vec.1, 1, 1, colors::BLACK, 1.0);
+                    continue
+                }
+            };
+            let visible = tcod.fov.is_in_fov(x, y);
+            let wall = tile.block_sight;
+            let color = match (visible, wall) {
+                 // outside of field of view:
+                 (false, true) => tile.dark_color,
+                 (false, false) => tile.dark_color,
+                 // inside fov:
+                 (true, true) => colors::lerp(tile.light_color, tile.dark_color, ((((x - player.x).pow(2) + (y - player.y).pow(2)) as f32).sqrt() / player.torch_radius as f32).powi(2)),
+                 (true, false) => colors::lerp(tile.light_color, tile.dark_color, ((((x - player.x).pow(2) + (y - player.y).pow(2)) as f32).sqrt() / player.torch_radius as f32).powi(2)),
+            };
+
+            if tile.explored {
+                //show explored tiles only (any visible tile is explored already)
+                tcod.renderer.put_char(x - vec.0, y - vec.1, tile.char, colors::lerp(color, colors::BLACK, 0.25));
+                tcod.renderer.set_background(x - vec.0, y - vec.1, color);
+            } else {
+                tcod.renderer.fill(x - vec.0, y - vec.1, 1, 1, colors::BLACK, 1.0);
+            }
+        }
+    }
+
+    let mut to_draw : Vec<_> = objects.iter()
+        .filter(|o| o.always_visible || tcod.fov.is_in_fov(o.x, o.y))
+        .filter(|o| o.trap.is_none_or(|trap| !trap.hidden))
+        .collect();
+    to_draw.sort_by(|o1, o2| {
+        o1.blocks.cmp(&o2.blocks)
+    });
+    for object in &to_draw {
+        if tcod.fov.is_in_fov(object.x, object.y) {
+            object.draw(&mut *tcod.renderer, &camera);
+        }
+    }
+
+    tcod.minimap.draw(&mut *tcod.renderer, map, objects, &tcod.fov, camera.width());
+}
+
+#[cfg(feature = "tcod")]
+fn render_bar(renderer: &mut dyn Renderer,
+              x: i32,
+              y: i32,
+              total_width: i32,
+              name: &str,
+              value: i32,
+              maximum: i32,
+              bar_color: Color,
+              back_color: Color)
+{
+    // render a bar (HP, experience, etc). First calculate the width of the bar
+    let bar_width = (value as f32 / maximum as f32 * total_width as f32) as i32;
+
+    // render the background first
+    renderer.fill(x, y, total_width, 1, back_color, 1.0);
+
+    // now render the bar on top
+    if bar_width > 0 {
+        renderer.fill(x, y, bar_width, 1, bar_color, 1.0);
+    }
+
+    renderer.print(x + total_width / 2, y, &format!("{}: {}/{}", name, value, maximum),
+                   colors::WHITE, TextAlignment::Center);
+
+    
+}
+
+fn message<T: Into<String>>(messages: &mut Messages, message: T, color: Color, category: enums::MessageCategory) {
+    // the whole history is kept, the panel only shows the latest lines
+    messages.push(message.into(), color, category);
+}
+
+#[cfg(feature = "tcod")]
+fn menu<T: AsRef<str>>(header: &str, options: &[T], width: i32,
+                       renderer: &mut dyn Renderer) -> Option<usize> {
+    let header_height = renderer::text_height(header, width);
+    let height = options.len() as i32 + header_height;
+
+    // the menu window goes over the middle of the screen, letting a bit of the game show through
+    let x = SCREEN_WIDTH / 2 - width / 2;
+    let y = SCREEN_HEIGHT / 2 - height / 2;
+    renderer.fill(x, y, width, height, colors::WHITE, 0.7);
+    renderer.print_rect(x, y, width, header, colors::WHITE);
+
+    for (i, option_text) in options.iter().enumerate() {
+        let menu_letter = (b'a' + i as u8) as char;
+        let text = format!("({}) {}", menu_letter, option_text.as_ref());
+        renderer.print(x, y + header_height + i as i32, &text, colors::WHITE, TextAlignment::Left);
+
+    }
+
+    renderer.flush();
+
+    let key = renderer.wait_for_keypress();
+
+    if key.printable.is_alphabetic() {
+        let i = key.printable.to_ascii_lowercase() as usize - 'a' as usize;
+        if i < options.len() {
+            Some(i)
+        } else {
+            None
+        }
+    }
+    else {
+        None
+    }
+    
+}
+
+#[cfg(feature = "tcod")]
+fn inventory_menu(inventory: &Vec<Object>, header: &str, renderer: &mut dyn Renderer) -> Option<usize> {
+    // how a menu with each item of the inventory as an option
+    let options = if inventory.len() == 0 {
+        vec!["Inventorio vazio.".into()]
+    } else {
+        inventory.iter().map(|item| { item.name.clone() }).collect()
+    };
+
+
+    let inventory_index = menu(header, &options, INVENTORY_WIDTH, renderer);
+
+    // if an item was chosen, return it
+    if inventory.len() > 0 {
+        inventory_index
+    } else {
+        None
+    }
+}
+
+/// lets the player aim at an enemy within `max_range`, `None` if there is none or the player gave up.
+/// The cursor starts on the closest enemy and moves with the arrows, WASD or the mouse, Tab jumps
+/// to the next enemy, Enter, Space or a left click fires and Escape or a right click cancels
+#[cfg(feature = "tcod")]
+fn target_monster(max_range: i32, objects: &[Object], messages: &mut Messages, map: &Map, tcod: &mut Tcod) -> Option<usize> {
+    use renderer::KeyCode::*;
+
+    let player = &objects[PLAYER];
+    let mut enemies: Vec<usize> = objects.iter().enumerate()
+        .filter(|&(id, object)| {
+            id != PLAYER && object.fighter.is_some() && object.ai.is_some() &&
+                tcod.fov.is_in_fov(object.x, object.y) && player.distance_to(object) <= max_range as f32
+        })
+        .map(|(id, _)| id)
+        .collect();
+    if enemies.is_empty() {
+        message(messages, "Nenhum inimigo por perto.", colors::RED, enums::MessageCategory::Item);
+        return None;
+    }
+    enemies.sort_by(|&a, &b| player.distance_to(&objects[a]).partial_cmp(&player.distance_to(&objects[b])).unwrap());
+
+    let mut enemy = 0;
+    let mut cursor = objects[enemies[enemy]].pos();
+    loop {
+        let line = line_of_fire(player.pos(), cursor, map, objects);
+        let target = visible_target(&line, objects, &tcod.fov);
+
+        render_map(tcod, objects, map);
+        render_targeting(tcod, player.pos(), max_range, &line, cursor);
+        let hint = match target {
+            Some(id) => format!("Alvo: {} - Enter dispara, Tab troca, Esc cancela", objects[id].name),
+            None => "Nenhum alvo na linha de tiro - Esc cancela".into(),
+        };
+        tcod.renderer.print(1, 0, &hint, colors::WHITE, TextAlignment::Left);
+        tcod.renderer.flush();
+
+        let (mut next, mut fire) = (cursor, false);
+        match tcod.renderer.check_for_event() {
+            Some(Event::Mouse(mouse)) => {
+                tcod.mouse = mouse;
+                if mouse.rbutton_pressed {
+                    return None;
+                }
+                // a click off the map aims at nothing
+                match tcod.camera.to_map(mouse.cx, mouse.cy) {
+                    Some(position) => {
+                        next = position;
+                        fire = mouse.lbutton_pressed;
+                    }
+                    None => continue,
+                }
+            }
+            Some(Event::Key(key)) => match (key.code, key.printable) {
+                (Escape, _) => return None,
+                (Enter, _) | (Spacebar, _) => fire = true,
+                (Tab, _) => {
+                    enemy = (enemy + 1) % enemies.len();
+                    next = objects[enemies[enemy]].pos();
+                }
+                (Up, _) | (_, 'w') => next.1 -= 1,
+                (Down, _) | (_, 's') => next.1 += 1,
+                (Left, _) | (_, 'a') => next.0 -= 1,
+                (Right, _) | (_, 'd') => next.0 += 1,
+                _ => (),
+            },
+            None => (),
+        }
+        if tcod.renderer.window_closed() {
+            return None;
+        }
+
+        // the cursor stays within range, on screen and in view, and a click elsewhere doesn't fire
+        let accepted = player.distance(next.0, next.1) <= max_range as f32 &&
+            tcod.camera.to_screen(next.0, next.1).is_some() && tcod.fov.is_in_fov(next.0, next.1);
+        if !accepted {
+            continue;
+        }
+        cursor = next;
+        if fire {
+            let line = line_of_fire(player.pos(), cursor, map, objects);
+            if let Some(target) = visible_target(&line, objects, &tcod.fov) {
+                return Some(target);
+            }
+        }
+    }
+}
+
+/// the tiles a bolt flies over from `from` to `to`, stopping short of walls and at the first creature
+#[cfg(any(feature = "tcod", test))]
+fn line_of_fire(from: (i32, i32), to: (i32, i32), map: &Map, objects: &[Object]) -> Vec<(i32, i32)> {
+    let mut line = vec![];
+    if from == to {
+        return line;
+    }
+    // the iterator starts at `from` and stops right before `to`
+    let points = Bresenham::new((from.0 as isize, from.1 as isize), (to.0 as isize, to.1 as isize))
+        .skip(1)
+        .map(|(x, y)| (x as i32, y as i32))
+        .chain(Some(to));
+    for (x, y) in points {
+        if map.get(x, y).is_none_or(|tile| tile.blocked) {
+            break;
+        }
+        line.push((x, y));
+        if monster_at(x, y, objects).is_some() {
+            break;
+        }
+    }
+    line
+}
+
+/// the creature the line of fire ends on, if the player can see it
+#[cfg(feature = "tcod")]
+fn visible_target(line: &[(i32, i32)], objects: &[Object], fov: &FovMap) -> Option<usize> {
+    line.last()
+        .filter(|&&(x, y)| fov.is_in_fov(x, y))
+        .and_then(|&(x, y)| monster_at(x, y, objects))
+}
+
+#[cfg(any(feature = "tcod", test))]
+fn monster_at(x: i32, y: i32, objects: &[Object]) -> Option<usize> {
+    objects.iter().enumerate()
+        .find(|&(id, object)| id != PLAYER && object.fighter.is_some() && object.pos() == (x, y))
+        .map(|(id, _)| id)
+}
+
+/// tints the tiles in range, the line of fire and the cursor over what `render_map` drew
+#[cfg(feature = "tcod")]
+fn render_targeting(tcod: &mut Tcod, (player_x, player_y): (i32, i32), max_range: i32, line: &[(i32, i32)], cursor: (i32, i32)) {
+    let camera = tcod.camera;
+    for y in (player_y - max_range)..(player_y + max_range + 1) {
+        for x in (player_x - max_range)..(player_x + max_range + 1) {
+            let in_range = (((x - player_x).pow(2) + (y - player_y).pow(2)) as f32).sqrt() <= max_range as f32;
+            if let Some((sx, sy)) = camera.to_screen(x, y) {
+                if in_range && tcod.fov.is_in_fov(x, y) {
+                    tcod.renderer.tint(sx, sy, COLOR_TARGET_RANGE, 0.2);
+                }
+            }
+        }
+    }
+    for &(x, y) in line {
+        if let Some((sx, sy)) = camera.to_screen(x, y) {
+            tcod.renderer.tint(sx, sy, COLOR_TARGET_LINE, 0.5);
+        }
+    }
+    if let Some((sx, sy)) = camera.to_screen(cursor.0, cursor.1) {
+        tcod.renderer.tint(sx, sy, COLOR_TARGET_CURSOR, 0.7);
+    }
+}
+
+#[cfg(feature = "tcod")]
+fn use_item(inventory_id: usize, inventory: &mut Vec<Object>, objects: &mut [Object],
+            messages: &mut Messages, map: &Map, tcod: &mut Tcod, rng: &mut GameRng) {
+    use enums::ItemType::*;
+    // just call the "use_function" if it is defined
+    let object = inventory.iter().nth(inventory_id).expect("Error").clone();
+    if let Some(item) = object.item {
+        let on_use = match item.item_type {
+            Heal => cast_heal,
+            Damage => cast_damage,
+            FireBolt => cast_fire_bolt,
+            Confuse => cast_confuse,
+            Scare => cast_scare,
+            Merge => cast_merge
+        };
+        match on_use(inventory_id, objects, messages, item, map, tcod, rng) {
+            enums::UseResult::UsedUp => {
+                // destroy after use, unless it was cancelled for some reason
+                inventory.remove(inventory_id);
+            }
+            enums::UseResult::Cancelled => {
+                message(messages, "Acao cancelada", colors::WHITE, enums::MessageCategory::Item);
+            }
+        }
+    } else {
+        message(messages,
+                format!("Item {} nao pode ser usado.", inventory[inventory_id].name),
+                colors::WHITE, enums::MessageCategory::Item);
+    }
+}
+
+#[cfg(feature = "tcod")]
+fn cast_scare(_inventory_id: usize, objects: &mut [Object], messages: &mut Messages, item: Item, map: &Map, tcod: &mut Tcod, _rng: &mut GameRng) -> enums::UseResult {
+    let monster_id = target_monster(item.range as i32, objects, messages, map, tcod);
+    if let Some(monster_id) = monster_id {
+        let old_ai = objects[monster_id].ai.take().unwrap_or(enums::Ai::Basic);
+        // replace the monster's AI with a "confused" one; after
+        // some turns it will restore the old AI
+        objects[monster_id].ai = Some(enums::Ai::Scared {
+            previous: Box::new(old_ai),
+            turns: 5,
+        });
+        message(messages,
+                format!("{} tem medo de voce e foge!",
+                        objects[monster_id].name),
+                colors::LIGHT_GREEN, enums::MessageCategory::Item);
+        enums::UseResult::UsedUp
+    } else {  // no enemy in range or the targeting was cancelled
+        enums::UseResult::Cancelled
+    }
+}
+
+#[cfg(feature = "tcod")]
+fn cast_merge(_inventory_id: usize, objects: &mut [Object], messages: &mut Messages, item: Item, map: &Map, tcod: &mut Tcod, _rng: &mut GameRng) -> enums::UseResult {
+    let monster_id = target_monster(item.range as i32, objects, messages, map, tcod);
+    let mut player = objects[PLAYER].clone();
+    if let Some(monster_id) = monster_id {
+        message(messages, format!("Voce se funde com {}.", objects[monster_id].name), colors::BLUE, enums::MessageCategory::Item);
+        objects[PLAYER] = player.merge(&mut objects[monster_id]);
+        let fighter = objects[monster_id].fighter;
+        objects[monster_id].take_damage(fighter.map_or(0, |f| f.max_hp), messages);
+        enums::UseResult::UsedUp
+    }
+    else {
+        enums::UseResult::Cancelled
+    }
+}
+
+
+#[cfg(feature = "tcod")]
+fn cast_confuse(_inventory_id: usize, objects: &mut [Object], messages: &mut Messages, item: Item, map: &Map, tcod: &mut Tcod, _rng: &mut GameRng) -> enums::UseResult {
+    let monster_id = target_monster(item.range as i32, objects, messages, map, tcod);
+    
+    if let Some(monster_id) = monster_id {
+        let old_ai = objects[monster_id].ai.take().unwrap_or(enums::Ai::Basic);
+        // replace the monster's AI with a "confused" one; after
+        // some turns it will restore the old AI
+        objects[monster_id].ai = Some(enums::Ai::Confused {
+            previous: Box::new(old_ai),
+            turns: 5,
+        });
+        message(messages,
+                format!("{} esta confuso!",
+                        objects[monster_id].name),
+                colors::LIGHT_GREEN, enums::MessageCategory::Item);
+        enums::UseResult::UsedUp
+    } else {  // no enemy in range or the targeting was cancelled
+        enums::UseResult::Cancelled
+    }
+}
+
+
+#[cfg(feature = "tcod")]
+fn cast_fire_bolt(_inventory_id: usize, objects: &mut [Object], messages: &mut Messages, item: Item, map: &Map, tcod: &mut Tcod, _rng: &mut GameRng) -> enums::UseResult {
+    let monster_id = target_monster(item.range as i32, objects, messages, map, tcod);
+    if let Some(monster_id) = monster_id {
+        message(messages, format!("Uma bola de fogo atingiu o {}!\nO hit foi de {}", objects[monster_id].name, item.amount), colors::BLUE, enums::MessageCategory::Item);
+        objects[monster_id].take_damage(item.amount as i32, messages);
+        enums::UseResult::UsedUp
+    }
+    else {
+        enums::UseResult::Cancelled
+    }
+}
+
+
+#[cfg(feature = "tcod")]
+fn cast_damage(_inventory_id: usize, objects: &mut [Object], messages: &mut Messages, item: Item, _map: &Map, _tcod: &mut Tcod, _rng: &mut GameRng) -> enums::UseResult {
+    // heal the player
+    if let Some(fighter) = objects[PLAYER].fighter {
+        
+        if fighter.hp < 1 {
+            message(messages, "Voce ja esta morto", colors::RED, enums::MessageCategory::Item);
+            return enums::UseResult::Cancelled;
+        }
+        message(messages, "Voce foi ferido!", colors::LIGHT_VIOLET, enums::MessageCategory::Item);
+        objects[PLAYER].take_damage(item.amount as i32, messages);
+        return enums::UseResult::UsedUp;
+        
+    }
+   enums::UseResult::Cancelled
+}
+
+#[cfg(feature = "tcod")]
+fn cast_heal(_inventory_id: usize, objects: &mut [Object], messages: &mut Messages, item: Item, _map: &Map, _tcod: &mut Tcod, rng: &mut GameRng) -> enums::UseResult {
+    // heal the player
+    if let Some(fighter) = objects[PLAYER].fighter {
+        
+        if fighter.hp == fighter.max_hp {
+            message(messages, "Voce ja tem a vida cheia.", colors::RED, enums::MessageCategory::Item);
+            return enums::UseResult::Cancelled;
+        }
+        let dice = rng.gen_range(0, 3);
+        if dice > 0 {
+            message(messages, format!("A cura lhe custou {} de mana.", dice) , colors::RED, enums::MessageCategory::Item);
+            objects[PLAYER].take_mana(dice);
+        }
+
+        message(messages, "Voce se sente melhor!", colors::LIGHT_VIOLET, enums::MessageCategory::Item);
+        objects[PLAYER].heal(item.amount as i32);
+        return enums::UseResult::UsedUp;
+        
+    }
+    enums::UseResult::Cancelled
+}
+
+pub fn arg_value(name: &str) -> Option<String> {
+    // value that follows `name` on the command line, if any
+    let args : Vec<String> = env::args().collect();
+    args.iter().position(|arg| arg == name).map(|index| {
+        args.get(index + 1).cloned().unwrap_or_else(|| panic!("{} precisa de um valor", name))
+    })
+}
+
+pub fn parse_debug() -> bool {
+    // `--debug` prints how every level was generated
+    env::args().any(|arg| arg == "--debug")
+}
+
+pub fn parse_seed() -> u32 {
+    // `--seed N` reproduces a previous run, otherwise a fresh seed is rolled
+    match arg_value("--seed") {
+        Some(aux) => aux.parse().expect("--seed precisa ser um numero inteiro positivo"),
+        None => rand::random::<u32>()
+    }
+}
+
+pub fn parse_size(name: &str, default: i32) -> i32 {
+    // `--width N` and `--height N` set the size of every new level
+    let size = arg_value(name).map_or(default, |aux| {
+        aux.parse().unwrap_or_else(|_| panic!("{} precisa ser um numero inteiro", name))
+    });
+    assert!(size >= MAP_MIN_SIZE, "{} precisa ser pelo menos {}", name, MAP_MIN_SIZE);
+    size
+}
+
+pub fn parse_generator() -> enums::Generator {
+    // `--generator rooms|perlin|caves|maze` picks the level style, rooms by default
+    match arg_value("--generator").as_deref() {
+        Some("perlin") => enums::Generator::Perlin,
+        Some("caves") => enums::Generator::Caves,
+        Some("maze") => enums::Generator::Maze,
+        Some("rooms") | None => enums::Generator::Rooms,
+        Some(other) => panic!("Gerador desconhecido: {}", other)
+    }
+}
+
+pub fn parse_layout() -> enums::RoomLayout {
+    // `--layout overlapping|bsp` picks how the rooms generator lays out its rooms
+    match arg_value("--layout").as_deref() {
+        Some("bsp") => enums::RoomLayout::Bsp,
+        Some("overlapping") | None => enums::RoomLayout::Overlapping,
+        Some(other) => panic!("Layout desconhecido: {}", other)
+    }
+}
+
+pub fn parse_corridors() -> Corridors {
+    // `--corridors straight|winding` and `--corridor-width 1..3` pick how rooms are joined
+    let style = match arg_value("--corridors").as_deref() {
+        Some("winding") => enums::CorridorStyle::Winding,
+        Some("straight") | None => enums::CorridorStyle::Straight,
+        Some(other) => panic!("Estilo de corredor desconhecido: {}", other)
+    };
+    let width = arg_value("--corridor-width").map_or(CORRIDOR_DEFAULT_WIDTH, |aux| {
+        aux.parse().expect("--corridor-width precisa ser um numero inteiro")
+    });
+    assert!((1..=CORRIDOR_MAX_WIDTH).contains(&width), "--corridor-width precisa estar entre 1 e {}", CORRIDOR_MAX_WIDTH);
+    Corridors { style: style, width: width }
+}
+
+/// every generation option on the command line
+pub fn parse_generation() -> GenerationConfig {
+    GenerationConfig {
+        width: parse_size("--width", MAP_WIDTH),
+        height: parse_size("--height", MAP_HEIGHT),
+        generator: parse_generator(),
+        layout: parse_layout(),
+        corridors: parse_corridors(),
+        braid: parse_braid(),
+    }
+}
+
+pub fn parse_backend() -> enums::Backend {
+    // `--renderer tcod|terminal` draws in a window or straight on the terminal, over SSH for instance
+    match arg_value("--renderer").as_deref() {
+        Some("terminal") => enums::Backend::Terminal,
+        Some("tcod") | None => enums::Backend::Tcod,
+        Some(other) => panic!("Renderer desconhecido: {}", other)
+    }
+}
+
+pub fn parse_camera() -> enums::CameraMode {
+    // `--camera centered|deadzone|edge` picks how the view follows the player
+    match arg_value("--camera").as_deref() {
+        Some("deadzone") => enums::CameraMode::DeadZone,
+        Some("edge") => enums::CameraMode::EdgeScroll,
+        Some("centered") | None => enums::CameraMode::Centered,
+        Some(other) => panic!("Modo de camera desconhecido: {}", other)
+    }
+}
+
+pub fn parse_braid() -> f32 {
+    // `--braid F` opens that fraction of the maze's dead ends, 0 keeps it a perfect maze
+    let braid = arg_value("--braid").map_or(MAZE_BRAID, |aux| {
+        aux.parse().expect("--braid precisa ser um numero")
+    });
+    assert!((0.0..=1.0).contains(&braid), "--braid precisa estar entre 0 e 1");
+    braid
+}
+
+/// opens the game window, or takes over the terminal, and plays until it is closed
+#[cfg(feature = "tcod")]
+pub fn run() {
+
+    DEBUG.store(parse_debug(), Ordering::Relaxed);
+    let seed = parse_seed();
+    let mut rng = GameRng::from_seed(&[seed as usize][..]);
+    let mut dungeon = Dungeon::new(seed, parse_generation());
+    // before the terminal backend takes over the screen
+    eprintln!("Seed: {}", seed);
+
+    let renderer: Box<dyn Renderer> = match parse_backend() {
+        enums::Backend::Tcod => Box::new(renderer::TcodRenderer::new(SCREEN_WIDTH, SCREEN_HEIGHT, LIMIT_FPS)),
+        enums::Backend::Terminal => Box::new(renderer::TerminalRenderer::new(SCREEN_WIDTH, SCREEN_HEIGHT)),
+    };
+
+    let mut tcod = Tcod {
+        renderer: renderer,
+        fov:  FovMap::new(dungeon.config.width, dungeon.config.height),
+        mouse: Default::default(),
+        // the panel covers the bottom of the screen, the map is shown above it
+        camera: Camera::new(parse_camera(), SCREEN_WIDTH, PANEL_Y),
+        minimap: minimap::Minimap::new(),
+    };
+
+    let player = Object::player(SCREEN_WIDTH / 2, SCREEN_HEIGHT / 2);
+
+    
+
+
+    let mut inventory = vec![];
+    // the list of objects with those two
+    let mut objects = vec![player];
+
+    let (mut map, stats) = match make_level(dungeon.depth, &dungeon, &mut objects) {
+        Ok(level) => level,
+        Err(error) => {
+            eprintln!("{}", error);
+            return;
+        }
+    };
+    print_level_stats(dungeon.depth, &dungeon, &stats);
+
+    let player = objects[PLAYER].clone();
+
+    tcod.camera.center_on((player.x, player.y), &map);
+
+    //let mut inventory = Vec<Object>[];
+
+    initialise_fov(&map, &mut tcod);
+
+    let mut messages = Messages::new();
+
+    message(&mut messages, "Bem vindo!", colors::RED, enums::MessageCategory::System);
+
+    render_all(&mut tcod, &mut objects, &mut map, true, &messages, &dungeon);
+    // the terminal waits for the first key before the loop flushes, show the level already
+    tcod.renderer.flush();
+
+    while !tcod.renderer.window_closed() {
+       
+        if let Some(fighter) = objects[PLAYER].fighter {
+            tcod.renderer.print(1, SCREEN_HEIGHT - 2, &format!("HP: {}/{} ", fighter.hp, fighter.max_hp),
+                                colors::WHITE, TextAlignment::Left);
+        }
+
+        let key : Key = match tcod.renderer.check_for_event() {
+            Some(Event::Mouse(m)) => {
+                tcod.mouse = m;
+                Default::default()
+            },
+            Some(Event::Key(k)) => {
+                k
+            },
+            _ => {
+                Default::default()
+            },
+        };
+
+        let depth = dungeon.depth;
+        let (old_x, old_y) = objects[PLAYER].pos();
+        let (player_action, option) = handle_keys(key, &mut map, &mut objects, &mut messages, &mut inventory, &mut tcod, &mut rng, &mut dungeon);
+        if dungeon.depth != depth || objects[PLAYER].distance(old_x, old_y) > 2.0 {
+            // a new floor or a teleport, the camera jumps straight to the player
+            tcod.camera.center_on(objects[PLAYER].pos(), &map);
+        }
+        if option.is_some() {
+            objects[PLAYER] = option.unwrap();
+        }
+        if player_action == enums::PlayerAction::Exit {
+            break
+        }
+        tcod.camera.follow(objects[PLAYER].pos(), &map);
+
+        if objects[PLAYER].alive && player_action != enums::PlayerAction::DidntTakeTurn {
+            // while the player wades through slow terrain the monsters keep acting
+            let turns = 1 + objects[PLAYER].wait;
+            objects[PLAYER].wait = 0;
+            for _ in 0..turns {
+                for id in 0..objects.len() {
+                    if objects[id].ai.is_some() {
+                        ai_take_turn(id, &map, &mut objects, &mut tcod.fov, &mut messages, &mut rng)
+                    }
+                }
+                messages.next_turn();
+            }
+            drop_fallen_monsters(&mut objects, &mut dungeon);
+        }
+
+        render_all(&mut tcod, &mut objects, &mut map, key != Default::default(), &messages, &dungeon);
+
+        tcod.renderer.flush();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rooms_config(width: i32, height: i32) -> GenerationConfig {
+        GenerationConfig {
+            width: width,
+            height: height,
+            generator: enums::Generator::Rooms,
+            layout: enums::RoomLayout::Overlapping,
+            corridors: Corridors { style: enums::CorridorStyle::Straight, width: CORRIDOR_DEFAULT_WIDTH },
+            braid: MAZE_BRAID,
+        }
+    }
+
+    #[test]
+    fn vaults_never_wall_off_the_player_or_the_stairs() {
+        // seeds whose vaults used to land on the border or on an earlier vault's door
+        let cases = [(60, 40, 97, 10), (60, 40, 135, 10), (60, 40, 161, 10), (200, 32, 1, 200)];
+        for &(width, height, first_seed, count) in &cases {
+            let mut dungeon = Dungeon::new(first_seed, rooms_config(width, height));
+            for seed in first_seed..(first_seed + count) {
+                dungeon.set_seed(seed);
+                let mut objects = vec![Object::player(0, 0)];
+                let (_, stats) = make_level(1, &dungeon, &mut objects)
+                    .unwrap_or_else(|error| panic!("seed {} em {}x{}: {}", seed, width, height, error));
+                // the level came out right the first time, not from a derived seed
+                assert_eq!(stats.attempts, 1, "seed {} em {}x{}", seed, width, height);
+            }
+        }
+    }
+
+    fn corridor() -> Map {
+        let mut map = Map::new(10, 5, Tile::empty());
+        map[(7, 2)] = Tile::wall();
+        map
+    }
+
+    #[test]
+    fn line_of_fire_reaches_the_target() {
+        let objects = vec![Object::player(1, 2)];
+        assert_eq!(line_of_fire((1, 2), (4, 2), &corridor(), &objects), vec![(2, 2), (3, 2), (4, 2)]);
+        assert!(line_of_fire((1, 2), (1, 2), &corridor(), &objects).is_empty());
+    }
+
+    #[test]
+    fn line_of_fire_stops_short_of_walls() {
+        let objects = vec![Object::player(1, 2)];
+        assert_eq!(line_of_fire((5, 2), (9, 2), &corridor(), &objects), vec![(6, 2)]);
+    }
+
+    #[test]
+    fn line_of_fire_stops_at_the_first_creature() {
+        let objects = vec![Object::player(1, 2), spawn_monster("troll", 3, 2).unwrap(), spawn_monster("goblin", 5, 2).unwrap()];
+        let line = line_of_fire((1, 2), (5, 2), &corridor(), &objects);
+        assert_eq!(line, vec![(2, 2), (3, 2)]);
+        assert_eq!(monster_at(3, 2, &objects), Some(1));
+        assert_eq!(monster_at(1, 2, &objects), None);
+    }
+}
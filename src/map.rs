@@ -0,0 +1,89 @@
+use std::ops::{Index, IndexMut};
+
+use Tile;
+
+const NEIGHBORS: [(i32, i32); 4] = [(-1, 0), (1, 0), (0, -1), (0, 1)];
+
+/// the tiles of a level, with its own size so levels don't all have to be as big
+#[derive(Clone, Debug)]
+pub struct Map {
+    width: i32,
+    height: i32,
+    tiles: Vec<Tile>,
+}
+
+impl Map {
+    pub fn new(width: i32, height: i32, tile: Tile) -> Self {
+        Map { width: width, height: height, tiles: vec![tile; (width * height) as usize] }
+    }
+
+    pub fn width(&self) -> i32 {
+        self.width
+    }
+
+    pub fn height(&self) -> i32 {
+        self.height
+    }
+
+    pub fn in_bounds(&self, x: i32, y: i32) -> bool {
+        x >= 0 && y >= 0 && x < self.width && y < self.height
+    }
+
+    pub fn get(&self, x: i32, y: i32) -> Option<&Tile> {
+        if self.in_bounds(x, y) {
+            Some(&self.tiles[self.index(x, y)])
+        } else {
+            None
+        }
+    }
+
+    pub fn get_mut(&mut self, x: i32, y: i32) -> Option<&mut Tile> {
+        if self.in_bounds(x, y) {
+            let index = self.index(x, y);
+            Some(&mut self.tiles[index])
+        } else {
+            None
+        }
+    }
+
+    /// the orthogonal neighbours of the position that are inside the map
+    pub fn neighbors(&self, x: i32, y: i32) -> impl Iterator<Item = (i32, i32)> {
+        let (width, height) = (self.width, self.height);
+        NEIGHBORS.iter()
+            .map(move |&(dx, dy)| (x + dx, y + dy))
+            .filter(move |&(nx, ny)| nx >= 0 && ny >= 0 && nx < width && ny < height)
+    }
+
+    /// every position of the map, column by column
+    pub fn positions(&self) -> impl Iterator<Item = (i32, i32)> {
+        let height = self.height;
+        (0..self.width).flat_map(move |x| (0..height).map(move |y| (x, y)))
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &Tile> {
+        self.tiles.iter()
+    }
+
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = &mut Tile> {
+        self.tiles.iter_mut()
+    }
+
+    fn index(&self, x: i32, y: i32) -> usize {
+        (x * self.height + y) as usize
+    }
+}
+
+impl Index<(i32, i32)> for Map {
+    type Output = Tile;
+
+    fn index(&self, (x, y): (i32, i32)) -> &Tile {
+        self.get(x, y).unwrap_or_else(|| panic!("({}, {}) esta fora do mapa {}x{}", x, y, self.width, self.height))
+    }
+}
+
+impl IndexMut<(i32, i32)> for Map {
+    fn index_mut(&mut self, (x, y): (i32, i32)) -> &mut Tile {
+        let (width, height) = (self.width, self.height);
+        self.get_mut(x, y).unwrap_or_else(|| panic!("({}, {}) esta fora do mapa {}x{}", x, y, width, height))
+    }
+}
@@ -0,0 +1,166 @@
+use std::cmp;
+use std::collections::HashMap;
+
+use tcod::map::Map as FovMap;
+
+use colors::{self, Color};
+use map::Map;
+use renderer::{KeyCode, Renderer, TextAlignment};
+use {Object, PLAYER};
+
+/// size in cells of the overlay in the corner of the view
+const CORNER_WIDTH: i32 = 28;
+const CORNER_HEIGHT: i32 = 14;
+/// how many map tiles each side of a minimap cell covers
+const CORNER_SCALE: i32 = 4;
+const FULL_SCALE: i32 = 4;
+const MAX_SCALE: i32 = 12;
+/// how many cells a key press scrolls the full-screen map
+const FULL_SCROLL_STEP: i32 = 5;
+
+/// the overlay in the corner of the view, following the player unless it was scrolled away
+pub struct Minimap {
+    pub visible: bool,
+    /// how far it was scrolled from the player, in cells
+    scroll: (i32, i32),
+}
+
+impl Minimap {
+    pub fn new() -> Self {
+        Minimap { visible: false, scroll: (0, 0) }
+    }
+
+    pub fn toggle(&mut self) {
+        self.visible = !self.visible;
+        self.scroll = (0, 0);
+    }
+
+    /// scrolls by `dx` x `dy` cells, stopping where the edge of the map comes into view
+    pub fn scroll(&mut self, dx: i32, dy: i32, map: &Map, objects: &[Object]) {
+        let scroll = (self.scroll.0 + dx, self.scroll.1 + dy);
+        self.scroll = clamp_scroll(map, objects[PLAYER].pos(), scroll, (CORNER_WIDTH, CORNER_HEIGHT), CORNER_SCALE);
+    }
+
+    /// draws the overlay in the top right corner of a view `view_width` cells wide
+    pub fn draw(&self, renderer: &mut dyn Renderer, map: &Map, objects: &[Object], fov: &FovMap, view_width: i32) {
+        if !self.visible {
+            return;
+        }
+        let (x, y) = (view_width - CORNER_WIDTH - 1, 1);
+        // a frame around it so it doesn't blend with the level behind
+        renderer.fill(x - 1, y - 1, CORNER_WIDTH + 2, CORNER_HEIGHT + 2, colors::DARK_GREY, 1.0);
+        let origin = origin(map, objects[PLAYER].pos(), self.scroll, (CORNER_WIDTH, CORNER_HEIGHT), CORNER_SCALE);
+        draw_area(renderer, map, objects, fov, (x, y, CORNER_WIDTH, CORNER_HEIGHT), origin, CORNER_SCALE);
+    }
+}
+
+/// takes over the screen with the whole explored level until a key other than
+/// the arrows, WASD or the zoom keys is pressed
+pub fn show_full(renderer: &mut dyn Renderer, map: &Map, objects: &[Object], fov: &FovMap) {
+    let (width, height) = (renderer.width(), renderer.height() - 1);
+    let mut scale = FULL_SCALE;
+    let mut scroll = (0, 0);
+    loop {
+        renderer.fill(0, 0, renderer.width(), renderer.height(), colors::BLACK, 1.0);
+        let origin = origin(map, objects[PLAYER].pos(), scroll, (width, height), scale);
+        draw_area(renderer, map, objects, fov, (0, 0, width, height), origin, scale);
+        renderer.print(1, height, &format!("Mapa 1:{} - setas movem, +/- zoom, outra tecla fecha", scale),
+                       colors::LIGHT_GREY, TextAlignment::Left);
+        renderer.flush();
+
+        let key = renderer.wait_for_keypress();
+        match (key.code, key.printable) {
+            (KeyCode::Up, _) | (_, 'w') => scroll.1 -= FULL_SCROLL_STEP,
+            (KeyCode::Down, _) | (_, 's') => scroll.1 += FULL_SCROLL_STEP,
+            (KeyCode::Left, _) | (_, 'a') => scroll.0 -= FULL_SCROLL_STEP,
+            (KeyCode::Right, _) | (_, 'd') => scroll.0 += FULL_SCROLL_STEP,
+            (_, '+') => scale = cmp::max(1, scale - 1),
+            (_, '-') => scale = cmp::min(MAX_SCALE, scale + 1),
+            _ => break,
+        }
+        scroll = clamp_scroll(map, objects[PLAYER].pos(), scroll, (width, height), scale);
+    }
+}
+
+/// the map position of the first cell, with the player in the middle unless the map's edge is in the way
+fn origin(map: &Map, (x, y): (i32, i32), scroll: (i32, i32), (width, height): (i32, i32), scale: i32) -> (i32, i32) {
+    let x = x - width / 2 * scale + scroll.0 * scale;
+    let y = y - height / 2 * scale + scroll.1 * scale;
+    (cmp::max(0, cmp::min(x, map.width() - width * scale)),
+     cmp::max(0, cmp::min(y, map.height() - height * scale)))
+}
+
+/// the scroll kept within the range that still moves the origin, so scrolling
+/// back from past the map's edge starts moving right away
+fn clamp_scroll(map: &Map, (x, y): (i32, i32), scroll: (i32, i32), (width, height): (i32, i32), scale: i32) -> (i32, i32) {
+    (clamp_axis(x - width / 2 * scale, scroll.0, map.width() - width * scale, scale),
+     clamp_axis(y - height / 2 * scale, scroll.1, map.height() - height * scale, scale))
+}
+
+/// the scroll along one axis between the last one with the origin at 0 and the first one at `last`
+fn clamp_axis(start: i32, scroll: i32, last: i32, scale: i32) -> i32 {
+    let lowest = (-start as f32 / scale as f32).floor() as i32;
+    let highest = ((last - start) as f32 / scale as f32).ceil() as i32;
+    cmp::max(lowest, cmp::min(scroll, highest))
+}
+
+/// draws the map from `origin` on, every cell standing for `scale` x `scale` tiles,
+/// and only the explored ones
+fn draw_area(renderer: &mut dyn Renderer, map: &Map, objects: &[Object], fov: &FovMap,
+             (screen_x, screen_y, width, height): (i32, i32, i32, i32), origin: (i32, i32), scale: i32) {
+    let markers = markers(map, objects, fov, origin, scale);
+    for cy in 0..height {
+        for cx in 0..width {
+            let (x, y) = (screen_x + cx, screen_y + cy);
+            let background = cell_color(map, origin.0 + cx * scale, origin.1 + cy * scale, scale);
+            renderer.fill(x, y, 1, 1, background.unwrap_or(colors::BLACK), 1.0);
+            if let Some(&(_, glyph, color)) = markers.get(&(cx, cy)) {
+                renderer.put_char(x, y, glyph, color);
+            }
+        }
+    }
+}
+
+/// the floor color if any floor in the block was explored, otherwise the wall color if a wall was
+fn cell_color(map: &Map, x: i32, y: i32, scale: i32) -> Option<Color> {
+    let mut wall = None;
+    for ty in y..(y + scale) {
+        for tx in x..(x + scale) {
+            match map.get(tx, ty) {
+                Some(tile) if tile.explored && !tile.blocked => return Some(tile.light_color),
+                Some(tile) if tile.explored => wall = Some(tile.dark_color),
+                _ => (),
+            }
+        }
+    }
+    wall
+}
+
+/// the glyph shown in each cell that has something worth marking, the player over the stairs over the items
+fn markers(map: &Map, objects: &[Object], fov: &FovMap, origin: (i32, i32), scale: i32) -> HashMap<(i32, i32), (u8, char, Color)> {
+    let mut markers = HashMap::new();
+    for (id, object) in objects.iter().enumerate() {
+        let marker = if id == PLAYER {
+            (3, '@', colors::WHITE)
+        } else if object.name.starts_with("stairs") {
+            (2, object.char, colors::MAGENTA)
+        } else if object.item.is_some() {
+            (1, '!', colors::YELLOW)
+        } else {
+            continue;
+        };
+        // the stairs stay where they were seen, items only show while they are in view
+        // since they could have been picked up or moved since
+        let explored = map.get(object.x, object.y).is_some_and(|tile| tile.explored);
+        let seen = id == PLAYER || (object.always_visible && explored) || fov.is_in_fov(object.x, object.y);
+        if !seen || object.x < origin.0 || object.y < origin.1 {
+            continue;
+        }
+        let cell = ((object.x - origin.0) / scale, (object.y - origin.1) / scale);
+        let entry = markers.entry(cell).or_insert(marker);
+        if entry.0 < marker.0 {
+            *entry = marker;
+        }
+    }
+    markers
+}
@@ -0,0 +1,93 @@
+use rand::Rng;
+
+/// Improved gradient (Perlin) noise with a permutation table drawn from the game RNG,
+/// so the same seed always produces the same terrain
+pub struct Perlin {
+    permutation: Vec<usize>,
+}
+
+impl Perlin {
+    pub fn new<R: Rng>(rng: &mut R) -> Self {
+        let mut table: Vec<usize> = (0..256).collect();
+        rng.shuffle(&mut table);
+
+        // duplicate the table so lookups never need to wrap
+        let mut permutation = table.clone();
+        permutation.extend(table);
+        Perlin { permutation: permutation }
+    }
+
+    /// raw noise value at the given point, roughly in the range [-1, 1]
+    pub fn get(&self, x: f64, y: f64, z: f64) -> f64 {
+        let p = &self.permutation;
+
+        // unit cube that contains the point
+        let xi = (x.floor() as i64 & 255) as usize;
+        let yi = (y.floor() as i64 & 255) as usize;
+        let zi = (z.floor() as i64 & 255) as usize;
+
+        // relative position of the point inside the cube
+        let x1 = x - x.floor();
+        let y1 = y - y.floor();
+        let z1 = z - z.floor();
+
+        let u = fade(x1);
+        let v = fade(y1);
+        let w = fade(z1);
+
+        // hash coordinates of the 8 cube corners
+        let a = p[xi] + yi;
+        let aa = p[a] + zi;
+        let ab = p[a + 1] + zi;
+        let b = p[xi + 1] + yi;
+        let ba = p[b] + zi;
+        let bb = p[b + 1] + zi;
+
+        // and add blended results from the 8 corners of the cube
+        lerp(w, lerp(v, lerp(u, grad(p[aa], x1, y1, z1),
+                                grad(p[ba], x1 - 1.0, y1, z1)),
+                        lerp(u, grad(p[ab], x1, y1 - 1.0, z1),
+                                grad(p[bb], x1 - 1.0, y1 - 1.0, z1))),
+                lerp(v, lerp(u, grad(p[aa + 1], x1, y1, z1 - 1.0),
+                                grad(p[ba + 1], x1 - 1.0, y1, z1 - 1.0)),
+                        lerp(u, grad(p[ab + 1], x1, y1 - 1.0, z1 - 1.0),
+                                grad(p[bb + 1], x1 - 1.0, y1 - 1.0, z1 - 1.0))))
+    }
+
+    /// sum of several octaves of noise, normalized to the range [0, 1]
+    pub fn octaves(&self, x: f64, y: f64, z: f64, octaves: u32, persistence: f64) -> f64 {
+        let mut total = 0.0;
+        let mut frequency = 1.0;
+        let mut amplitude = 1.0;
+        let mut max_value = 0.0;
+        for _ in 0..octaves {
+            total += self.get(x * frequency, y * frequency, z * frequency) * amplitude;
+            max_value += amplitude;
+            amplitude *= persistence;
+            frequency *= 2.0;
+        }
+        ((total / max_value) + 1.0) / 2.0
+    }
+}
+
+fn fade(t: f64) -> f64 {
+    t * t * t * (t * (t * 6.0 - 15.0) + 10.0)
+}
+
+fn lerp(t: f64, a: f64, b: f64) -> f64 {
+    a + t * (b - a)
+}
+
+fn grad(hash: usize, x: f64, y: f64, z: f64) -> f64 {
+    // convert the low 4 bits of the hash code into 12 gradient directions
+    let h = hash & 15;
+    let u = if h < 8 { x } else { y };
+    let v = if h < 4 {
+        y
+    } else if h == 12 || h == 14 {
+        x
+    } else {
+        z
+    };
+    (if h & 1 == 0 { u } else { -u }) + (if h & 2 == 0 { v } else { -v })
+}
@@ -0,0 +1,248 @@
+use std::collections::HashMap;
+use std::fs::{self, File};
+use std::io::Read;
+use std::path::Path;
+
+use {spawn_item, spawn_monster};
+
+/// directory the hand-authored vaults are loaded from
+pub const PREFABS_DIR: &str = "prefabs";
+
+/// what a legend character stands for
+#[derive(Clone, Debug, PartialEq)]
+pub enum PrefabCell {
+    Wall,
+    Floor,
+    Water,
+    DeepWater,
+    Door,
+    Monster(String),
+    Item(String),
+}
+
+/// a set piece read from a text file: a legend, then a character grid.
+/// Spaces in the grid are left untouched when the prefab is stamped, and
+/// a door on its outer edge is where the tunnels come in
+///
+/// ```text
+/// ; comments start with a semicolon
+/// legend
+/// # wall
+/// . floor
+/// T monster troll
+/// ! item heal_potion
+/// + door
+/// grid
+/// #####
+/// #.T!#
+/// ##+##
+/// ```
+#[derive(Clone, Debug)]
+pub struct Prefab {
+    pub name: String,
+    pub legend: HashMap<char, PrefabCell>,
+    pub rows: Vec<Vec<char>>,
+}
+
+impl Prefab {
+    pub fn parse(name: &str, contents: &str) -> Result<Self, String> {
+        let mut legend = HashMap::new();
+        let mut rows: Vec<Vec<char>> = vec![];
+        let mut section = "";
+
+        for (number, line) in contents.lines().enumerate() {
+            let line = line.trim_end();
+            if line.starts_with(';') || (section != "grid" && line.trim().is_empty()) {
+                continue;
+            }
+            if line == "legend" || line == "grid" {
+                section = if line == "legend" { "legend" } else { "grid" };
+                continue;
+            }
+            match section {
+                "legend" => {
+                    let symbol = line.chars().next().unwrap();
+                    let words: Vec<&str> = line[symbol.len_utf8()..].split_whitespace().collect();
+                    let cell = match (words.first().cloned(), words.get(1)) {
+                        (Some("wall"), _) => PrefabCell::Wall,
+                        (Some("floor"), _) => PrefabCell::Floor,
+                        (Some("water"), _) => PrefabCell::Water,
+                        (Some("deep_water"), _) => PrefabCell::DeepWater,
+                        (Some("door"), _) => PrefabCell::Door,
+                        (Some("monster"), Some(monster)) => PrefabCell::Monster(monster.to_string()),
+                        (Some("item"), Some(item)) => PrefabCell::Item(item.to_string()),
+                        _ => return Err(format!("linha {}: legenda invalida '{}'", number + 1, line)),
+                    };
+                    legend.insert(symbol, cell);
+                }
+                "grid" => rows.push(line.chars().collect()),
+                _ => return Err(format!("linha {}: esperava 'legend' ou 'grid'", number + 1)),
+            }
+        }
+
+        // drop trailing blank lines and make every row the same width
+        while rows.last().is_some_and(|row| row.is_empty()) {
+            rows.pop();
+        }
+        if rows.is_empty() {
+            return Err("grid vazio".into());
+        }
+        let width = rows.iter().map(|row| row.len()).max().unwrap();
+        for row in rows.iter_mut() {
+            row.resize(width, ' ');
+        }
+        for row in &rows {
+            if let Some(symbol) = row.iter().find(|&&symbol| symbol != ' ' && !legend.contains_key(&symbol)) {
+                return Err(format!("'{}' nao esta na legenda", symbol));
+            }
+        }
+
+        let prefab = Prefab { name: name.to_string(), legend: legend, rows: rows };
+        if prefab.entrance().is_none() {
+            return Err("nenhuma porta na borda".into());
+        }
+        Ok(prefab)
+    }
+
+    pub fn width(&self) -> i32 {
+        self.rows[0].len() as i32
+    }
+
+    pub fn height(&self) -> i32 {
+        self.rows.len() as i32
+    }
+
+    /// what goes at the given position of the grid, `None` for the untouched spaces
+    pub fn cell(&self, x: i32, y: i32) -> Option<&PrefabCell> {
+        self.legend.get(&self.rows[y as usize][x as usize])
+    }
+
+    /// the position just outside the first door on the prefab's outer edge, relative to
+    /// its top left corner, so it can be a tile past the grid
+    pub fn entrance(&self) -> Option<(i32, i32)> {
+        for y in 0..self.height() {
+            for x in 0..self.width() {
+                if self.cell(x, y) != Some(&PrefabCell::Door) {
+                    continue;
+                }
+                for &(dx, dy) in &[(0, -1), (0, 1), (-1, 0), (1, 0)] {
+                    let (ox, oy) = (x + dx, y + dy);
+                    let outside = ox < 0 || oy < 0 || ox >= self.width() || oy >= self.height() ||
+                        self.cell(ox, oy).is_none();
+                    if outside {
+                        return Some((ox, oy));
+                    }
+                }
+            }
+        }
+        None
+    }
+
+    /// the same prefab turned 90 degrees clockwise
+    pub fn rotated(&self) -> Self {
+        let (width, height) = (self.width() as usize, self.height() as usize);
+        let rows = (0..width)
+            .map(|x| (0..height).rev().map(|y| self.rows[y][x]).collect())
+            .collect();
+        Prefab { name: self.name.clone(), legend: self.legend.clone(), rows: rows }
+    }
+
+    /// the same prefab flipped left to right
+    pub fn mirrored(&self) -> Self {
+        let rows = self.rows.iter()
+            .map(|row| row.iter().rev().cloned().collect())
+            .collect();
+        Prefab { name: self.name.clone(), legend: self.legend.clone(), rows: rows }
+    }
+}
+
+/// reads every `.txt` file in the prefabs directory, skipping the broken ones
+pub fn load_prefabs() -> Vec<Prefab> {
+    let mut prefabs = vec![];
+    let entries = match fs::read_dir(PREFABS_DIR) {
+        Ok(entries) => entries,
+        Err(error) => {
+            eprintln!("Nao foi possivel ler {} ({}), nenhum prefab carregado.", PREFABS_DIR, error);
+            return prefabs;
+        }
+    };
+
+    let mut paths: Vec<_> = entries.filter_map(|entry| entry.ok()).map(|entry| entry.path()).collect();
+    // the directory order is up to the OS, sort it so a seed always picks the same prefabs
+    paths.sort();
+    for path in paths {
+        if path.extension().is_none_or(|extension| extension != "txt") {
+            continue;
+        }
+        match read_prefab(&path) {
+            Ok(prefab) => prefabs.push(prefab),
+            Err(error) => eprintln!("Prefab {} ignorado: {}", path.display(), error),
+        }
+    }
+    prefabs
+}
+
+fn read_prefab(path: &Path) -> Result<Prefab, String> {
+    let mut contents = String::new();
+    File::open(path)
+        .and_then(|mut file| file.read_to_string(&mut contents))
+        .map_err(|error| error.to_string())?;
+    let name = path.file_stem().and_then(|stem| stem.to_str()).unwrap_or("prefab");
+    let prefab = Prefab::parse(name, &contents)?;
+    for cell in prefab.legend.values() {
+        let known = match *cell {
+            PrefabCell::Monster(ref name) => spawn_monster(name, 0, 0).is_some(),
+            PrefabCell::Item(ref name) => spawn_item(name, 0, 0).is_some(),
+            _ => true,
+        };
+        if !known {
+            return Err(format!("nao sabe criar {:?}", cell));
+        }
+    }
+    Ok(prefab)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const FONTE: &str = "; a test vault
+legend
+# wall
+. floor
+T monster troll
++ door
+grid
+####
+#.T#
+##+#
+";
+
+    #[test]
+    fn parse_reads_the_legend_and_pads_the_grid() {
+        let prefab = Prefab::parse("fonte", &FONTE.replace("#.T#", "#.T")).unwrap();
+        assert_eq!((prefab.width(), prefab.height()), (4, 3));
+        assert_eq!(prefab.cell(2, 1), Some(&PrefabCell::Monster("troll".into())));
+        assert_eq!(prefab.cell(3, 1), None);
+        assert_eq!(prefab.entrance(), Some((2, 3)));
+    }
+
+    #[test]
+    fn parse_turns_down_broken_prefabs() {
+        assert!(Prefab::parse("fonte", &FONTE.replace("+ door", "+ portal")).is_err());
+        assert!(Prefab::parse("fonte", &FONTE.replace("#.T#", "#.X#")).is_err());
+        assert!(Prefab::parse("fonte", &FONTE.replace("##+#", "####")).is_err());
+        assert!(Prefab::parse("fonte", "legend\n# wall\ngrid\n").is_err());
+    }
+
+    #[test]
+    fn rotated_turns_clockwise_and_back() {
+        let prefab = Prefab::parse("fonte", FONTE).unwrap();
+        let rotated = prefab.rotated();
+        assert_eq!((rotated.width(), rotated.height()), (3, 4));
+        assert_eq!(rotated.rows[2], vec!['+', 'T', '#']);
+        assert_eq!(rotated.entrance(), Some((-1, 2)));
+        let back = rotated.rotated().rotated().rotated();
+        assert_eq!(back.rows, prefab.rows);
+    }
+}
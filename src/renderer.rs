@@ -0,0 +1,464 @@
+use std::cmp;
+use std::io::{self, Read, Write};
+use std::process::{Command, Stdio};
+
+#[cfg(feature = "tcod")]
+use tcod;
+#[cfg(feature = "tcod")]
+use tcod::console::{Console, FontLayout, FontType, Root, BackgroundFlag};
+#[cfg(feature = "tcod")]
+use tcod::input;
+use colors::{self, Color};
+
+/// the keys the game tells apart, every other one with a glyph is a `Char`
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum KeyCode {
+    #[default]
+    NoKey,
+    Escape,
+    Backspace,
+    Tab,
+    Enter,
+    Spacebar,
+    PageUp,
+    PageDown,
+    Left,
+    Up,
+    Right,
+    Down,
+    Char,
+}
+
+/// a key press, `printable` is the glyph it types or `'\0'`
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Key {
+    pub code: KeyCode,
+    pub printable: char,
+    pub pressed: bool,
+    pub alt: bool,
+}
+
+/// where the mouse is, in screen cells, and whether a button was just clicked
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Mouse {
+    pub cx: i32,
+    pub cy: i32,
+    pub lbutton_pressed: bool,
+    pub rbutton_pressed: bool,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Event {
+    Key(Key),
+    Mouse(Mouse),
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum TextAlignment {
+    Left,
+    Center,
+}
+
+/// what the game draws on and reads its input from, so it doesn't care whether
+/// it runs in a tcod window or in a plain terminal
+pub trait Renderer {
+    fn width(&self) -> i32;
+    fn height(&self) -> i32;
+    /// draws a glyph in the given color, keeping the background of the cell
+    fn put_char(&mut self, x: i32, y: i32, glyph: char, color: Color);
+    fn background(&self, x: i32, y: i32) -> Color;
+    fn set_background(&mut self, x: i32, y: i32, color: Color);
+    /// shows everything drawn since the last flush
+    fn flush(&mut self);
+    /// the next key press or mouse event, `None` if nothing happened
+    fn check_for_event(&mut self) -> Option<Event>;
+    fn wait_for_keypress(&mut self) -> Key;
+    fn window_closed(&self) -> bool;
+    fn toggle_fullscreen(&mut self);
+
+    fn in_bounds(&self, x: i32, y: i32) -> bool {
+        x >= 0 && y >= 0 && x < self.width() && y < self.height()
+    }
+
+    /// prints a single line, `x` is where the text starts or is centered on
+    fn print(&mut self, x: i32, y: i32, text: &str, color: Color, alignment: TextAlignment) {
+        let length = text.chars().count() as i32;
+        let start = match alignment {
+            TextAlignment::Left => x,
+            TextAlignment::Center => x - length / 2,
+        };
+        for (i, glyph) in text.chars().enumerate() {
+            self.put_char(start + i as i32, y, glyph, color);
+        }
+    }
+
+    /// prints the text wrapped to `width` columns and returns how many lines it took
+    fn print_rect(&mut self, x: i32, y: i32, width: i32, text: &str, color: Color) -> i32 {
+        let lines = wrap(text, width);
+        for (i, line) in lines.iter().enumerate() {
+            self.print(x, y + i as i32, line, color, TextAlignment::Left);
+        }
+        lines.len() as i32
+    }
+
+    /// moves the background of a cell `alpha` of the way towards `color`, keeping its glyph
+    fn tint(&mut self, x: i32, y: i32, color: Color, alpha: f32) {
+        if self.in_bounds(x, y) {
+            let background = colors::lerp(self.background(x, y), color, alpha);
+            self.set_background(x, y, background);
+        }
+    }
+
+    /// blanks the area and moves its background `alpha` of the way towards `color`
+    fn fill(&mut self, x: i32, y: i32, width: i32, height: i32, color: Color, alpha: f32) {
+        for y in y..(y + height) {
+            for x in x..(x + width) {
+                if self.in_bounds(x, y) {
+                    let background = colors::lerp(self.background(x, y), color, alpha);
+                    self.put_char(x, y, ' ', colors::WHITE);
+                    self.set_background(x, y, background);
+                }
+            }
+        }
+    }
+}
+
+/// how many lines the text takes once wrapped to `width` columns
+pub fn text_height(text: &str, width: i32) -> i32 {
+    wrap(text, width).len() as i32
+}
+
+/// breaks the text on its newlines and between words, cutting the words that don't fit in a line
+pub fn wrap(text: &str, width: i32) -> Vec<String> {
+    let width = cmp::max(width, 1) as usize;
+    let mut lines = vec![];
+    if text.is_empty() {
+        return lines;
+    }
+    for paragraph in text.split('\n') {
+        let mut line = String::new();
+        for word in paragraph.split(' ') {
+            let mut word: Vec<char> = word.chars().collect();
+            let length = line.chars().count();
+            if length > 0 && length + 1 + word.len() > width {
+                lines.push(line);
+                line = String::new();
+            }
+            while word.len() > width {
+                let rest = word.split_off(width);
+                lines.push(word.into_iter().collect());
+                word = rest;
+            }
+            if !line.is_empty() {
+                line.push(' ');
+            }
+            line.extend(word);
+        }
+        lines.push(line);
+    }
+    lines
+}
+
+/// draws on a libtcod window
+#[cfg(feature = "tcod")]
+pub struct TcodRenderer {
+    root: Root,
+}
+
+#[cfg(feature = "tcod")]
+impl TcodRenderer {
+    pub fn new(width: i32, height: i32, fps: i32) -> Self {
+        let root = Root::initializer()
+            .font("bluebox.png", FontLayout::AsciiInRow)
+            .font_type(FontType::Greyscale)
+            .size(width, height)
+            .title("Rogue")
+            .init();
+        tcod::system::set_fps(fps);
+        TcodRenderer { root: root }
+    }
+}
+
+#[cfg(feature = "tcod")]
+impl Renderer for TcodRenderer {
+    fn width(&self) -> i32 {
+        self.root.width()
+    }
+
+    fn height(&self) -> i32 {
+        self.root.height()
+    }
+
+    fn put_char(&mut self, x: i32, y: i32, glyph: char, color: Color) {
+        if self.in_bounds(x, y) {
+            self.root.set_char(x, y, glyph);
+            self.root.set_char_foreground(x, y, color);
+        }
+    }
+
+    fn background(&self, x: i32, y: i32) -> Color {
+        self.root.get_char_background(x, y)
+    }
+
+    fn set_background(&mut self, x: i32, y: i32, color: Color) {
+        if self.in_bounds(x, y) {
+            self.root.set_char_background(x, y, color, BackgroundFlag::Set);
+        }
+    }
+
+    fn flush(&mut self) {
+        self.root.flush();
+    }
+
+    fn check_for_event(&mut self) -> Option<Event> {
+        input::check_for_event(input::MOUSE | input::KEY_PRESS).map(|(_, event)| match event {
+            input::Event::Key(key) => Event::Key(from_tcod_key(key)),
+            input::Event::Mouse(mouse) => Event::Mouse(Mouse {
+                cx: mouse.cx as i32,
+                cy: mouse.cy as i32,
+                lbutton_pressed: mouse.lbutton_pressed,
+                rbutton_pressed: mouse.rbutton_pressed,
+            }),
+        })
+    }
+
+    fn wait_for_keypress(&mut self) -> Key {
+        from_tcod_key(self.root.wait_for_keypress(true))
+    }
+
+    fn window_closed(&self) -> bool {
+        self.root.window_closed()
+    }
+
+    fn toggle_fullscreen(&mut self) {
+        let fullscreen = self.root.is_fullscreen();
+        self.root.set_fullscreen(!fullscreen);
+    }
+}
+
+/// the key tcod reported as one of ours
+#[cfg(feature = "tcod")]
+fn from_tcod_key(key: input::Key) -> Key {
+    use tcod::input::KeyCode as Tcod;
+    let code = match key.code {
+        Tcod::Escape => KeyCode::Escape,
+        Tcod::Backspace => KeyCode::Backspace,
+        Tcod::Tab => KeyCode::Tab,
+        Tcod::Enter | Tcod::NumPadEnter => KeyCode::Enter,
+        Tcod::Spacebar => KeyCode::Spacebar,
+        Tcod::PageUp => KeyCode::PageUp,
+        Tcod::PageDown => KeyCode::PageDown,
+        Tcod::Left => KeyCode::Left,
+        Tcod::Up => KeyCode::Up,
+        Tcod::Right => KeyCode::Right,
+        Tcod::Down => KeyCode::Down,
+        _ if key.printable != '\0' => KeyCode::Char,
+        _ => KeyCode::NoKey,
+    };
+    Key { code: code, printable: key.printable, pressed: key.pressed, alt: key.alt }
+}
+
+#[derive(Clone, Copy)]
+struct Cell {
+    glyph: char,
+    foreground: Color,
+    background: Color,
+}
+
+const BLANK: Cell = Cell { glyph: ' ', foreground: colors::WHITE, background: colors::BLACK };
+
+/// draws with ANSI escape codes and truecolor on the terminal the game was started from,
+/// which needs to be at least as big as the screen. There is no mouse, and since nothing
+/// happens between key presses it waits for the keyboard instead of polling it
+pub struct TerminalRenderer {
+    width: i32,
+    height: i32,
+    cells: Vec<Cell>,
+    /// the `stty` settings to put back when the game ends
+    saved_mode: Option<String>,
+    closed: bool,
+}
+
+impl TerminalRenderer {
+    pub fn new(width: i32, height: i32) -> Self {
+        let saved_mode = stty(&["-g"]);
+        // raw mode hands us every key as it is pressed, without echoing it
+        stty(&["raw", "-echo"]);
+        // switch to the alternate screen and hide the cursor
+        print!("\x1b[?1049h\x1b[?25l");
+        let _ = io::stdout().flush();
+        TerminalRenderer {
+            width: width,
+            height: height,
+            cells: vec![BLANK; (width * height) as usize],
+            saved_mode: saved_mode,
+            closed: false,
+        }
+    }
+
+    fn index(&self, x: i32, y: i32) -> usize {
+        (y * self.width + x) as usize
+    }
+
+    fn read_key(&mut self) -> Key {
+        // an escape sequence arrives in a single read, a lone escape is the Escape key
+        let mut bytes = [0; 8];
+        match io::stdin().read(&mut bytes) {
+            Ok(0) | Err(_) => {
+                self.closed = true;
+                key(KeyCode::Escape, '\0')
+            }
+            Ok(read) => parse_key(&bytes[..read]),
+        }
+    }
+}
+
+impl Renderer for TerminalRenderer {
+    fn width(&self) -> i32 {
+        self.width
+    }
+
+    fn height(&self) -> i32 {
+        self.height
+    }
+
+    fn put_char(&mut self, x: i32, y: i32, glyph: char, color: Color) {
+        if self.in_bounds(x, y) {
+            let index = self.index(x, y);
+            self.cells[index].glyph = glyph;
+            self.cells[index].foreground = color;
+        }
+    }
+
+    fn background(&self, x: i32, y: i32) -> Color {
+        self.cells[self.index(x, y)].background
+    }
+
+    fn set_background(&mut self, x: i32, y: i32, color: Color) {
+        if self.in_bounds(x, y) {
+            let index = self.index(x, y);
+            self.cells[index].background = color;
+        }
+    }
+
+    fn flush(&mut self) {
+        // the whole screen is written every time, so anything else printed to the terminal is painted over
+        let mut out = String::new();
+        for y in 0..self.height {
+            out.push_str(&format!("\x1b[{};1H", y + 1));
+            let mut current: Option<(Color, Color)> = None;
+            for x in 0..self.width {
+                let cell = self.cells[self.index(x, y)];
+                if current != Some((cell.foreground, cell.background)) {
+                    let (fg, bg) = (cell.foreground, cell.background);
+                    out.push_str(&format!("\x1b[38;2;{};{};{};48;2;{};{};{}m", fg.r, fg.g, fg.b, bg.r, bg.g, bg.b));
+                    current = Some((fg, bg));
+                }
+                out.push(cell.glyph);
+            }
+        }
+        out.push_str("\x1b[0m");
+        let stdout = io::stdout();
+        let mut stdout = stdout.lock();
+        let _ = stdout.write_all(out.as_bytes());
+        let _ = stdout.flush();
+    }
+
+    fn check_for_event(&mut self) -> Option<Event> {
+        Some(Event::Key(self.read_key()))
+    }
+
+    fn wait_for_keypress(&mut self) -> Key {
+        self.read_key()
+    }
+
+    fn window_closed(&self) -> bool {
+        self.closed
+    }
+
+    fn toggle_fullscreen(&mut self) {
+        // the terminal's size is up to the terminal
+    }
+}
+
+impl Drop for TerminalRenderer {
+    fn drop(&mut self) {
+        print!("\x1b[0m\x1b[?25h\x1b[?1049l");
+        let _ = io::stdout().flush();
+        if let Some(ref mode) = self.saved_mode {
+            stty(&[mode.as_str()]);
+        }
+    }
+}
+
+/// runs `stty` on the game's terminal, returning what it printed if it worked
+fn stty(args: &[&str]) -> Option<String> {
+    Command::new("stty")
+        .args(args)
+        .stdin(Stdio::inherit())
+        .output()
+        .ok()
+        .filter(|output| output.status.success())
+        .map(|output| String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+fn key(code: KeyCode, printable: char) -> Key {
+    Key { code: code, printable: printable, pressed: true, ..Default::default() }
+}
+
+/// turns the bytes the terminal sent for a key press into the key tcod would have reported
+fn parse_key(bytes: &[u8]) -> Key {
+    match bytes {
+        [0x1b, b'[', b'A', ..] | [0x1b, b'O', b'A', ..] => key(KeyCode::Up, '\0'),
+        [0x1b, b'[', b'B', ..] | [0x1b, b'O', b'B', ..] => key(KeyCode::Down, '\0'),
+        [0x1b, b'[', b'C', ..] | [0x1b, b'O', b'C', ..] => key(KeyCode::Right, '\0'),
+        [0x1b, b'[', b'D', ..] | [0x1b, b'O', b'D', ..] => key(KeyCode::Left, '\0'),
+        [0x1b, b'[', b'5', b'~', ..] => key(KeyCode::PageUp, '\0'),
+        [0x1b, b'[', b'6', b'~', ..] => key(KeyCode::PageDown, '\0'),
+        // ctrl+c quits like escape does, since raw mode doesn't turn it into a signal
+        [0x1b] | [3, ..] => key(KeyCode::Escape, '\0'),
+        // Home, End, the function keys, alt+letter and the like do nothing
+        [0x1b, ..] => Default::default(),
+        [b'\r', ..] | [b'\n', ..] => key(KeyCode::Enter, '\r'),
+        [0x7f, ..] | [8, ..] => key(KeyCode::Backspace, '\0'),
+        [b' ', ..] => key(KeyCode::Spacebar, ' '),
+        [b'\t', ..] => key(KeyCode::Tab, '\t'),
+        [byte, ..] if byte.is_ascii_graphic() => key(KeyCode::Char, *byte as char),
+        _ => Default::default(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn wrap_breaks_between_words() {
+        assert_eq!(wrap("o orc ataca o troll", 8), vec!["o orc", "ataca o", "troll"]);
+    }
+
+    #[test]
+    fn wrap_keeps_newlines_and_cuts_long_words() {
+        assert_eq!(wrap("abcdefgh\nij", 3), vec!["abc", "def", "gh", "ij"]);
+        assert!(wrap("", 10).is_empty());
+        assert_eq!(text_height("a b c", 1), 3);
+    }
+
+    #[test]
+    fn parse_key_reads_arrows_and_printables() {
+        assert_eq!(parse_key(b"\x1b[A").code, KeyCode::Up);
+        assert_eq!(parse_key(b"\x1bOD").code, KeyCode::Left);
+        assert_eq!(parse_key(b"\x1b[6~").code, KeyCode::PageDown);
+        assert_eq!(parse_key(b"\r").code, KeyCode::Enter);
+        let key = parse_key(b"g");
+        assert_eq!((key.code, key.printable), (KeyCode::Char, 'g'));
+    }
+
+    #[test]
+    fn parse_key_only_takes_a_lone_escape_as_escape() {
+        assert_eq!(parse_key(b"\x1b").code, KeyCode::Escape);
+        assert_eq!(parse_key(&[3]).code, KeyCode::Escape);
+        assert!(parse_key(b"\x1b[H").code != KeyCode::Escape);
+        assert!(parse_key(b"\x1bx").code != KeyCode::Escape);
+    }
+}
@@ -0,0 +1,170 @@
+use std::fs::File;
+use std::io::Read;
+
+use rand::Rng;
+use serde_json;
+
+use {spawn_item, spawn_monster, spawn_trap};
+
+/// file with the spawn tables, read at startup so the weights can be tuned without recompiling
+pub const SPAWN_TABLES_FILE: &str = "spawn_tables.json";
+
+/// copy of the tables shipped with the game, used when the file can't be read
+const DEFAULT_SPAWN_TABLES: &str = include_str!("../spawn_tables.json");
+
+/// value that applies from dungeon level `level` onwards
+#[derive(Clone, Copy, Debug, Deserialize)]
+pub struct Transition {
+    pub level: i32,
+    pub value: u32,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct SpawnEntry {
+    pub name: String,
+    pub weights: Vec<Transition>,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct SpawnTables {
+    pub max_room_monsters: Vec<Transition>,
+    pub max_room_items: Vec<Transition>,
+    #[serde(default)]
+    pub max_room_traps: Vec<Transition>,
+    pub monsters: Vec<SpawnEntry>,
+    pub items: Vec<SpawnEntry>,
+    #[serde(default)]
+    pub traps: Vec<SpawnEntry>,
+}
+
+/// the spawn tables resolved for a single dungeon level
+#[derive(Clone, Debug)]
+pub struct SpawnTable {
+    pub max_monsters: i32,
+    pub max_items: i32,
+    pub max_traps: i32,
+    pub monsters: Vec<(String, u32)>,
+    pub items: Vec<(String, u32)>,
+    pub traps: Vec<(String, u32)>,
+}
+
+impl SpawnTables {
+    pub fn load() -> Self {
+        let mut contents = String::new();
+        let read = File::open(SPAWN_TABLES_FILE).and_then(|mut file| file.read_to_string(&mut contents));
+        if let Err(error) = read {
+            eprintln!("Nao foi possivel ler {} ({}), usando as tabelas padrao.", SPAWN_TABLES_FILE, error);
+            return SpawnTables::defaults();
+        }
+        match SpawnTables::parse(&contents) {
+            Ok(tables) => tables,
+            Err(error) => {
+                eprintln!("{} invalido ({}), usando as tabelas padrao.", SPAWN_TABLES_FILE, error);
+                SpawnTables::defaults()
+            }
+        }
+    }
+
+    /// the tables shipped with the game
+    fn defaults() -> Self {
+        SpawnTables::parse(DEFAULT_SPAWN_TABLES).expect("Tabelas de spawn padrao invalidas")
+    }
+
+    /// reads the tables, turning down any name the game doesn't know how to spawn
+    fn parse(contents: &str) -> Result<Self, String> {
+        let tables: SpawnTables = serde_json::from_str(contents).map_err(|error| error.to_string())?;
+        check_names(&tables.monsters, "monsters", |name| spawn_monster(name, 0, 0).is_some())?;
+        check_names(&tables.items, "items", |name| spawn_item(name, 0, 0).is_some())?;
+        check_names(&tables.traps, "traps", |name| spawn_trap(name, 0, 0).is_some())?;
+        Ok(tables)
+    }
+
+    pub fn for_level(&self, level: i32) -> SpawnTable {
+        let resolve = |entries: &Vec<SpawnEntry>| {
+            entries.iter()
+                .map(|entry| (entry.name.clone(), from_dungeon_level(&entry.weights, level)))
+                .collect()
+        };
+        SpawnTable {
+            max_monsters: from_dungeon_level(&self.max_room_monsters, level) as i32,
+            max_items: from_dungeon_level(&self.max_room_items, level) as i32,
+            max_traps: from_dungeon_level(&self.max_room_traps, level) as i32,
+            monsters: resolve(&self.monsters),
+            items: resolve(&self.items),
+            traps: resolve(&self.traps),
+        }
+    }
+}
+
+/// fails on the first entry of the `table` list whose name isn't `known`
+fn check_names<F: Fn(&str) -> bool>(entries: &[SpawnEntry], table: &str, known: F) -> Result<(), String> {
+    match entries.iter().find(|entry| !known(&entry.name)) {
+        Some(entry) => Err(format!("nome desconhecido em {}: {}", table, entry.name)),
+        None => Ok(()),
+    }
+}
+
+/// returns the value of the last transition reached at this level, 0 before the first one
+pub fn from_dungeon_level(table: &[Transition], level: i32) -> u32 {
+    table.iter()
+        .rev()
+        .find(|transition| level >= transition.level)
+        .map_or(0, |transition| transition.value)
+}
+
+/// picks a name from the table proportionally to its weight, `None` if every weight is 0
+pub fn choose<'a, R: Rng>(table: &'a [(String, u32)], rng: &mut R) -> Option<&'a str> {
+    let total: u32 = table.iter().map(|&(_, weight)| weight).sum();
+    if total == 0 {
+        return None;
+    }
+    let mut roll = rng.gen_range(0, total);
+    for &(ref name, weight) in table {
+        if roll < weight {
+            return Some(name.as_str());
+        }
+        roll -= weight;
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::{SeedableRng, StdRng};
+
+    fn table(weights: &[(&str, u32)]) -> Vec<(String, u32)> {
+        weights.iter().map(|&(name, weight)| (name.to_string(), weight)).collect()
+    }
+
+    #[test]
+    fn choose_skips_the_names_without_weight() {
+        let mut rng = StdRng::from_seed(&[7usize][..]);
+        let table = table(&[("orc", 0), ("troll", 5), ("goblin", 0)]);
+        for _ in 0..50 {
+            assert_eq!(choose(&table, &mut rng), Some("troll"));
+        }
+    }
+
+    #[test]
+    fn choose_follows_the_weights() {
+        let mut rng = StdRng::from_seed(&[7usize][..]);
+        let table = table(&[("orc", 1), ("troll", 3)]);
+        let trolls = (0..4000).filter(|_| choose(&table, &mut rng) == Some("troll")).count();
+        assert!(trolls > 2800 && trolls < 3200, "{} trolls", trolls);
+    }
+
+    #[test]
+    fn choose_nothing_when_every_weight_is_zero() {
+        let mut rng = StdRng::from_seed(&[7usize][..]);
+        assert_eq!(choose(&table(&[("orc", 0)]), &mut rng), None);
+        assert_eq!(choose(&[], &mut rng), None);
+    }
+
+    #[test]
+    fn the_default_tables_parse_and_unknown_names_are_turned_down() {
+        SpawnTables::defaults();
+        let broken = DEFAULT_SPAWN_TABLES.replacen("\"troll\"", "\"dragao\"", 1);
+        assert!(SpawnTables::parse(&broken).is_err());
+    }
+}
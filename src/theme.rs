@@ -0,0 +1,82 @@
+use std::fs::File;
+use std::io::Read;
+
+use serde_json;
+use colors::Color;
+
+/// file with the level themes, read at startup like the spawn tables
+pub const THEMES_FILE: &str = "themes.json";
+
+/// copy of the themes shipped with the game, used when the file can't be read
+const DEFAULT_THEMES: &str = include_str!("../themes.json");
+
+/// colors of a kind of tile when it is in view and when it is only remembered
+#[derive(Clone, Copy, Debug, Deserialize)]
+pub struct Palette {
+    pub light: Color,
+    pub dark: Color,
+}
+
+/// how a level looks: tile colors and the glyphs scattered over its floor
+#[derive(Clone, Debug, Deserialize)]
+pub struct Theme {
+    #[cfg_attr(not(feature = "tcod"), allow(dead_code))]
+    pub name: String,
+    /// first dungeon level the theme can show up on
+    pub from_level: i32,
+    /// generators the theme is meant for, any generator when empty
+    #[serde(default)]
+    pub generators: Vec<String>,
+    pub wall: Palette,
+    pub ground: Palette,
+    pub water: Palette,
+    pub deep_water: Palette,
+    pub floor_glyphs: String,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct Themes {
+    pub themes: Vec<Theme>,
+}
+
+impl Themes {
+    pub fn load() -> Self {
+        let mut contents = String::new();
+        let read = File::open(THEMES_FILE).and_then(|mut file| file.read_to_string(&mut contents));
+        if let Err(error) = read {
+            eprintln!("Nao foi possivel ler {} ({}), usando os temas padrao.", THEMES_FILE, error);
+            return Themes::defaults();
+        }
+        match Themes::parse(&contents) {
+            Ok(themes) => themes,
+            Err(error) => {
+                eprintln!("{} invalido ({}), usando os temas padrao.", THEMES_FILE, error);
+                Themes::defaults()
+            }
+        }
+    }
+
+    /// the themes shipped with the game
+    fn defaults() -> Self {
+        Themes::parse(DEFAULT_THEMES).expect("Temas padrao invalidos")
+    }
+
+    /// reads the themes, which need at least one so every level has a look
+    fn parse(contents: &str) -> Result<Self, String> {
+        let themes: Themes = serde_json::from_str(contents).map_err(|error| error.to_string())?;
+        if themes.themes.is_empty() {
+            return Err("nenhum tema".into());
+        }
+        Ok(themes)
+    }
+
+    /// the deepest theme reached at this level that fits the generator. On the same
+    /// level a theme made for the generator wins over one made for any generator
+    pub fn for_level(&self, level: i32, generator: &str) -> &Theme {
+        self.themes.iter()
+            .filter(|theme| level >= theme.from_level)
+            .filter(|theme| theme.generators.is_empty() || theme.generators.iter().any(|name| name == generator))
+            .max_by_key(|theme| (theme.from_level, !theme.generators.is_empty()))
+            .unwrap_or(&self.themes[0])
+    }
+}